@@ -0,0 +1,113 @@
+use actix_web::{delete, get, post, web, HttpResponse};
+use core::{api_key_context::ApiKeyContext, error::Error};
+use types::enums::ApiKeyScope;
+use service::api_key_service::ApiKeyService;
+use crate::{
+    middlewares::require_scope::RequireScope,
+    dto::{
+        pagination_dto::PaginationDto,
+        api_key_dto::{
+            ApiKeyDto, ApiKeyIdPathParameterDto, CreateApiKeyDto,
+            CreatedApiKeyDto, PaginatedApiKeyDto
+        }
+    },
+};
+
+#[utoipa::path(
+    path = "/api_keys",
+    tag = "Api-Keys",
+    responses(
+        (status = 201, description = "Api key is created, its plain value is only returned once", body = CreatedApiKeyDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 401, description = "Missing or invalid api key", body = ErrorResponse, content_type = "application/json"),
+        (status = 403, description = "Api key lacks the admin scope", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[post("", wrap = "RequireScope(ApiKeyScope::Admin)")]
+pub async fn create_api_key(
+    mut dto: web::Json<CreateApiKeyDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    dto.validate()?;
+
+    let mut api_key_service = ApiKeyService::new();
+    let (api_key, key) = api_key_service.create(api_key_context.app_id, dto.name.trim().to_string(), dto.get_scopes()?).await?;
+
+    Ok(HttpResponse::Created().json(CreatedApiKeyDto {
+        key,
+        api_key: ApiKeyDto::from(&api_key)
+    }))
+}
+
+#[utoipa::path(
+    path = "/api_keys",
+    tag = "Api-Keys",
+    responses(
+        (status = 200, description = "List of api keys paginated", body = PaginatedApiKeyDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 401, description = "Missing or invalid api key", body = ErrorResponse, content_type = "application/json"),
+        (status = 403, description = "Api key lacks the admin scope", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[get("", wrap = "RequireScope(ApiKeyScope::Admin)")]
+pub async fn fetch_api_key_list_paginated(
+    mut pagination_info: web::Query<PaginationDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    pagination_info.validate()?;
+
+    let page = pagination_info.get_offset();
+    let max_result = pagination_info.get_limit();
+
+    let mut api_key_service = ApiKeyService::new();
+    let list_of_api_keys = api_key_service.fetch_api_key_paginated(api_key_context.app_id, page * max_result, max_result).await?;
+    let total_count = api_key_service.count(api_key_context.app_id).await?;
+
+    let total_page = (total_count + max_result - 1) / max_result;
+    let next_page: Option<i32> = if page + 1 < total_page { Option::from(page + 2) } else { None };
+    let previous_page: Option<i32> = if page > 0 { Option::from(page) } else { None };
+
+    Ok(HttpResponse::Ok().json(
+        PaginatedApiKeyDto {
+            total_count,
+            total_page,
+            next_page,
+            previous_page,
+            api_keys: list_of_api_keys.iter().map(|k| k.into()).collect()
+        }
+    ))
+}
+
+#[utoipa::path(
+    path = "/api_keys/{api_key_id}",
+    tag = "Api-Keys",
+    responses(
+        (status = 204, description = "Api key is revoked", body = String, content_type = "text/plain"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 401, description = "Missing or invalid api key", body = ErrorResponse, content_type = "application/json"),
+        (status = 403, description = "Api key lacks the admin scope", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "Api key not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[delete("/{api_key_id}", wrap = "RequireScope(ApiKeyScope::Admin)")]
+pub async fn revoke_api_key(
+    mut params: web::Path<ApiKeyIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    let api_key_id = params.get_id_or_error()?;
+
+    let mut api_key_service = ApiKeyService::new();
+    api_key_service.revoke(api_key_context.app_id, api_key_id.into()).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+pub fn web_api_keys() -> actix_web::Scope {
+    web::scope("/api_keys")
+        .service(create_api_key)
+        .service(fetch_api_key_list_paginated)
+        .service(revoke_api_key)
+}
\ No newline at end of file
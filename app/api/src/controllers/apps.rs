@@ -0,0 +1,142 @@
+use actix_web::{get, post, put, web, HttpResponse};
+use core::{api_key_context::ApiKeyContext, error::Error};
+use types::enums::ApiKeyScope;
+use service::app_service::AppService;
+use crate::{
+    middlewares::require_scope::RequireScope,
+    dto::{
+        pagination_dto::PaginationDto,
+        app_dto::{
+            AppDto, AppIdPathParameterDto, CreateAppDto,
+            PaginatedAppDto, UpdateAppDto
+        }
+    },
+};
+
+#[utoipa::path(
+    path = "/apps",
+    tag = "Apps",
+    responses(
+        (status = 201, description = "App is created with the templates of its notifications file", body = AppDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 401, description = "Missing or invalid api key", body = ErrorResponse, content_type = "application/json"),
+        (status = 403, description = "Api key is not the ADMIN_API_KEY", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[post("", wrap = "RequireScope(ApiKeyScope::Admin)")]
+pub async fn create_app(
+    mut dto: web::Json<CreateAppDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    api_key_context.check_bootstrap_key()?;
+    dto.validate()?;
+
+    let mut app_service = AppService::new();
+    let app = app_service.create(dto.get_notification_app()).await?;
+
+    Ok(HttpResponse::Created().json(AppDto::from(&app)))
+}
+
+#[utoipa::path(
+    path = "/apps",
+    tag = "Apps",
+    responses(
+        (status = 200, description = "List of apps paginated", body = PaginatedAppDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 401, description = "Missing or invalid api key", body = ErrorResponse, content_type = "application/json"),
+        (status = 403, description = "Api key is not the ADMIN_API_KEY", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[get("", wrap = "RequireScope(ApiKeyScope::Admin)")]
+pub async fn fetch_app_list_paginated(
+    mut pagination_info: web::Query<PaginationDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    api_key_context.check_bootstrap_key()?;
+    pagination_info.validate()?;
+
+    let page = pagination_info.get_offset();
+    let max_result = pagination_info.get_limit();
+
+    let mut app_service = AppService::new();
+    let list_of_apps = app_service.fetch_app_paginated(page * max_result, max_result).await?;
+    let total_count = app_service.count().await?;
+
+    let total_page = (total_count + max_result - 1) / max_result;
+    let next_page: Option<i32> = if page + 1 < total_page { Option::from(page + 2) } else { None };
+    let previous_page: Option<i32> = if page > 0 { Option::from(page) } else { None };
+
+    Ok(HttpResponse::Ok().json(
+        PaginatedAppDto {
+            total_count,
+            total_page,
+            next_page,
+            previous_page,
+            apps: list_of_apps.iter().map(|a| a.into()).collect()
+        }
+    ))
+}
+
+#[utoipa::path(
+    path = "/apps/{app_id}",
+    tag = "Apps",
+    responses(
+        (status = 200, description = "App", body = AppDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 401, description = "Missing or invalid api key", body = ErrorResponse, content_type = "application/json"),
+        (status = 403, description = "Api key is not the ADMIN_API_KEY", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "App not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[get("/{app_id}", wrap = "RequireScope(ApiKeyScope::Admin)")]
+pub async fn fetch_app(
+    mut params: web::Path<AppIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    api_key_context.check_bootstrap_key()?;
+    let app_id = params.get_id_or_error()?;
+
+    let mut app_service = AppService::new();
+    let app = app_service.find_app_by_id(app_id.into()).await?;
+
+    Ok(HttpResponse::Ok().json(AppDto::from(&app)))
+}
+
+#[utoipa::path(
+    path = "/apps/{app_id}",
+    tag = "Apps",
+    responses(
+        (status = 200, description = "App is updated, the consumer picks new credentials up on its next send", body = AppDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 401, description = "Missing or invalid api key", body = ErrorResponse, content_type = "application/json"),
+        (status = 403, description = "Api key is not the ADMIN_API_KEY", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "App not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[put("/{app_id}", wrap = "RequireScope(ApiKeyScope::Admin)")]
+pub async fn update_app(
+    mut dto: web::Json<UpdateAppDto>,
+    mut params: web::Path<AppIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    api_key_context.check_bootstrap_key()?;
+    let app_id = params.get_id_or_error()?;
+    dto.validate()?;
+
+    let mut app_service = AppService::new();
+    let app = app_service.update(app_id.into(), dto.get_notification_app()).await?;
+
+    Ok(HttpResponse::Ok().json(AppDto::from(&app)))
+}
+
+pub fn web_apps() -> actix_web::Scope {
+    web::scope("/apps")
+        .service(create_app)
+        .service(fetch_app_list_paginated)
+        .service(fetch_app)
+        .service(update_app)
+}
\ No newline at end of file
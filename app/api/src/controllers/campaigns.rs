@@ -0,0 +1,220 @@
+use actix_web::{get, post, web, HttpResponse};
+use core::{api_key_context::ApiKeyContext, error::Error};
+use types::enums::{ApiKeyScope, DeviceType};
+use service::{
+    campaign_service::CampaignService,
+    notification_template_service::NotificationTemplateService
+};
+use crate::{
+    middlewares::{idempotent::Idempotent, require_scope::RequireScope},
+    dto::{
+        pagination_dto::PaginationDto,
+        campaign_dto::{
+            CampaignDto, CampaignIdPathParameterDto,
+            CreateCampaignDto, PaginatedCampaignDto
+        }
+    },
+};
+
+#[utoipa::path(
+    path = "/campaigns",
+    tag = "Campaigns",
+    responses(
+        (status = 201, description = "Campaign is created as a draft", body = CampaignDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 409, description = "Idempotency-Key reused with a different request, or still in progress", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[post("", wrap = "Idempotent", wrap = "RequireScope(ApiKeyScope::Send)")]
+pub async fn create_campaign(
+    mut dto: web::Json<CreateCampaignDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    dto.validate()?;
+
+    let mut notification_template_service = NotificationTemplateService::new();
+    notification_template_service.validate_template_data(api_key_context.app_id, &dto.notification_type, &dto.template_data).await?;
+    // The audience may target both platforms, so the payload must fit every one of them.
+    for device_type in [DeviceType::Android, DeviceType::IOS] {
+        notification_template_service.validate_payload_size(
+            api_key_context.app_id,
+            &dto.notification_type,
+            &None,
+            &device_type,
+            &dto.template_data,
+            &dto.extra_data,
+            &dto.get_notification_options()
+        ).await?;
+    }
+
+    let mut campaign_service = CampaignService::new();
+    let campaign = campaign_service.create(api_key_context.app_id, dto.get_notification_campaign()?).await?;
+
+    Ok(HttpResponse::Created().json(CampaignDto::from(&campaign)))
+}
+
+#[utoipa::path(
+    path = "/campaigns",
+    tag = "Campaigns",
+    responses(
+        (status = 200, description = "List of campaigns paginated", body = PaginatedCampaignDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[get("", wrap = "RequireScope(ApiKeyScope::Read)")]
+pub async fn fetch_campaign_list_paginated(
+    mut pagination_info: web::Query<PaginationDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    pagination_info.validate()?;
+
+    let page = pagination_info.get_offset();
+    let max_result = pagination_info.get_limit();
+
+    let mut campaign_service = CampaignService::new();
+    let list_of_campaigns = campaign_service.fetch_campaign_paginated(api_key_context.app_id, page * max_result, max_result).await?;
+    let total_count = campaign_service.count(api_key_context.app_id).await?;
+
+    let total_page = (total_count + max_result - 1) / max_result;
+    let next_page: Option<i32> = if page + 1 < total_page { Option::from(page + 2) } else { None };
+    let previous_page: Option<i32> = if page > 0 { Option::from(page) } else { None };
+
+    Ok(HttpResponse::Ok().json(
+        PaginatedCampaignDto {
+            total_count,
+            total_page,
+            next_page,
+            previous_page,
+            campaigns: list_of_campaigns.iter().map(|c| c.into()).collect()
+        }
+    ))
+}
+
+#[utoipa::path(
+    path = "/campaigns/{campaign_id}",
+    tag = "Campaigns",
+    responses(
+        (status = 200, description = "Campaign with its delivery progress", body = CampaignDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "Campaign not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[get("/{campaign_id}", wrap = "RequireScope(ApiKeyScope::Read)")]
+pub async fn fetch_campaign(
+    mut params: web::Path<CampaignIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    let campaign_id = params.get_id_or_error()?;
+
+    let mut campaign_service = CampaignService::new();
+    let campaign = campaign_service.find_campaign_by_id(api_key_context.app_id, campaign_id.into()).await?;
+    let progress = campaign_service.get_progress(&campaign).await?;
+
+    Ok(HttpResponse::Ok().json(CampaignDto::from(&campaign).with_progress(&progress)))
+}
+
+#[utoipa::path(
+    path = "/campaigns/{campaign_id}/start",
+    tag = "Campaigns",
+    responses(
+        (status = 200, description = "Campaign is running, or scheduled when its scheduled_date is in the future", body = CampaignDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "Campaign not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[post("/{campaign_id}/start", wrap = "RequireScope(ApiKeyScope::Send)")]
+pub async fn start_campaign(
+    mut params: web::Path<CampaignIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    let campaign_id = params.get_id_or_error()?;
+
+    let mut campaign_service = CampaignService::new();
+    let campaign = campaign_service.start(api_key_context.app_id, campaign_id.into()).await?;
+
+    Ok(HttpResponse::Ok().json(CampaignDto::from(&campaign)))
+}
+
+#[utoipa::path(
+    path = "/campaigns/{campaign_id}/cancel",
+    tag = "Campaigns",
+    responses(
+        (status = 200, description = "Campaign is canceled, its queued notifications are dropped", body = CampaignDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "Campaign not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[post("/{campaign_id}/cancel", wrap = "RequireScope(ApiKeyScope::Send)")]
+pub async fn cancel_campaign(
+    mut params: web::Path<CampaignIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    let campaign_id = params.get_id_or_error()?;
+
+    let mut campaign_service = CampaignService::new();
+    let campaign = campaign_service.cancel(api_key_context.app_id, campaign_id.into()).await?;
+
+    Ok(HttpResponse::Ok().json(CampaignDto::from(&campaign)))
+}
+
+#[utoipa::path(
+    path = "/campaigns/{campaign_id}/pause",
+    tag = "Campaigns",
+    responses(
+        (status = 200, description = "Campaign is paused, its queued notifications are held until it is resumed", body = CampaignDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "Campaign not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[post("/{campaign_id}/pause", wrap = "RequireScope(ApiKeyScope::Send)")]
+pub async fn pause_campaign(
+    mut params: web::Path<CampaignIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    let campaign_id = params.get_id_or_error()?;
+
+    let mut campaign_service = CampaignService::new();
+    let campaign = campaign_service.pause(api_key_context.app_id, campaign_id.into()).await?;
+
+    Ok(HttpResponse::Ok().json(CampaignDto::from(&campaign)))
+}
+
+#[utoipa::path(
+    path = "/campaigns/{campaign_id}/resume",
+    tag = "Campaigns",
+    responses(
+        (status = 200, description = "Campaign is running again, or scheduled when its scheduled_date is in the future", body = CampaignDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "Campaign not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[post("/{campaign_id}/resume", wrap = "RequireScope(ApiKeyScope::Send)")]
+pub async fn resume_campaign(
+    mut params: web::Path<CampaignIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    let campaign_id = params.get_id_or_error()?;
+
+    let mut campaign_service = CampaignService::new();
+    let campaign = campaign_service.resume(api_key_context.app_id, campaign_id.into()).await?;
+
+    Ok(HttpResponse::Ok().json(CampaignDto::from(&campaign)))
+}
+
+pub fn web_campaigns() -> actix_web::Scope {
+    web::scope("/campaigns")
+        .service(create_campaign)
+        .service(fetch_campaign_list_paginated)
+        .service(fetch_campaign)
+        .service(start_campaign)
+        .service(pause_campaign)
+        .service(resume_campaign)
+        .service(cancel_campaign)
+}
\ No newline at end of file
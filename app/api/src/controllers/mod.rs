@@ -1,2 +1,7 @@
 pub mod health_check;
-pub mod users;
\ No newline at end of file
+pub mod users;
+pub mod schedules;
+pub mod templates;
+pub mod campaigns;
+pub mod api_keys;
+pub mod apps;
\ No newline at end of file
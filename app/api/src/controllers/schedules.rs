@@ -0,0 +1,172 @@
+use actix_web::{delete, get, post, web, HttpResponse};
+use core::{api_key_context::ApiKeyContext, error::Error};
+use types::enums::{ApiKeyScope, DeviceType};
+use service::{
+    notification_options::NotificationOptions,
+    notification_template_service::NotificationTemplateService,
+    schedule_service::ScheduleService
+};
+use crate::{
+    middlewares::{idempotent::Idempotent, require_scope::RequireScope},
+    dto::{
+        pagination_dto::PaginationDto,
+        schedule_dto::{
+            CreateScheduleDto, PaginatedScheduleDto,
+            ScheduleDto, ScheduleIdPathParameterDto
+        }
+    },
+};
+
+#[utoipa::path(
+    path = "/schedules",
+    tag = "Schedules",
+    responses(
+        (status = 201, description = "Schedule is created", body = ScheduleDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 409, description = "Idempotency-Key reused with a different request, or still in progress", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[post("", wrap = "Idempotent", wrap = "RequireScope(ApiKeyScope::Send)")]
+pub async fn create_schedule(
+    mut dto: web::Json<CreateScheduleDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    dto.validate()?;
+
+    let mut notification_template_service = NotificationTemplateService::new();
+    notification_template_service.validate_template_data(api_key_context.app_id, &dto.notification_type, &dto.template_data).await?;
+    // Targeted devices are only known when the schedule runs, so the payload must fit every platform.
+    for device_type in [DeviceType::Android, DeviceType::IOS] {
+        notification_template_service.validate_payload_size(
+            api_key_context.app_id,
+            &dto.notification_type,
+            &None,
+            &device_type,
+            &dto.template_data,
+            &dto.extra_data,
+            &NotificationOptions::default()
+        ).await?;
+    }
+
+    let mut schedule_service = ScheduleService::new();
+    let schedule = schedule_service.create(api_key_context.app_id, dto.get_notification_schedule()).await?;
+
+    Ok(HttpResponse::Created().json(ScheduleDto::from(&schedule)))
+}
+
+#[utoipa::path(
+    path = "/schedules",
+    tag = "Schedules",
+    responses(
+        (status = 200, description = "List of schedules paginated", body = PaginatedScheduleDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[get("", wrap = "RequireScope(ApiKeyScope::Read)")]
+pub async fn fetch_schedule_list_paginated(
+    mut pagination_info: web::Query<PaginationDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    pagination_info.validate()?;
+
+    let page = pagination_info.get_offset();
+    let max_result = pagination_info.get_limit();
+
+    let mut schedule_service = ScheduleService::new();
+    let list_of_schedules = schedule_service.fetch_schedule_paginated(api_key_context.app_id, page * max_result, max_result).await?;
+    let total_count = schedule_service.count(api_key_context.app_id).await?;
+
+    let total_page = (total_count + max_result - 1) / max_result;
+    let next_page: Option<i32> = if page + 1 < total_page { Option::from(page + 2) } else { None };
+    let previous_page: Option<i32> = if page > 0 { Option::from(page) } else { None };
+
+    Ok(HttpResponse::Ok().json(
+        PaginatedScheduleDto {
+            total_count,
+            total_page,
+            next_page,
+            previous_page,
+            schedules: list_of_schedules.iter().map(|s| s.into()).collect()
+        }
+    ))
+}
+
+#[utoipa::path(
+    path = "/schedules/{schedule_id}/pause",
+    tag = "Schedules",
+    responses(
+        (status = 204, description = "Schedule is paused", body = String, content_type = "text/plain"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "Schedule not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[post("/{schedule_id}/pause", wrap = "RequireScope(ApiKeyScope::Send)")]
+pub async fn pause_schedule(
+    mut params: web::Path<ScheduleIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    let schedule_id = params.get_id_or_error()?;
+
+    let mut schedule_service = ScheduleService::new();
+    schedule_service.pause(api_key_context.app_id, schedule_id.into()).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+#[utoipa::path(
+    path = "/schedules/{schedule_id}/resume",
+    tag = "Schedules",
+    responses(
+        (status = 204, description = "Schedule is resumed", body = String, content_type = "text/plain"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "Schedule not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[post("/{schedule_id}/resume", wrap = "RequireScope(ApiKeyScope::Send)")]
+pub async fn resume_schedule(
+    mut params: web::Path<ScheduleIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    let schedule_id = params.get_id_or_error()?;
+
+    let mut schedule_service = ScheduleService::new();
+    schedule_service.resume(api_key_context.app_id, schedule_id.into()).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+#[utoipa::path(
+    path = "/schedules/{schedule_id}",
+    tag = "Schedules",
+    responses(
+        (status = 204, description = "Schedule is deleted", body = String, content_type = "text/plain"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "Schedule not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[delete("/{schedule_id}", wrap = "RequireScope(ApiKeyScope::Send)")]
+pub async fn delete_schedule(
+    mut params: web::Path<ScheduleIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    let schedule_id = params.get_id_or_error()?;
+
+    let mut schedule_service = ScheduleService::new();
+    schedule_service.delete(api_key_context.app_id, schedule_id.into()).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+pub fn web_schedules() -> actix_web::Scope {
+    web::scope("/schedules")
+        .service(create_schedule)
+        .service(fetch_schedule_list_paginated)
+        .service(pause_schedule)
+        .service(resume_schedule)
+        .service(delete_schedule)
+}
\ No newline at end of file
@@ -0,0 +1,206 @@
+use actix_web::{delete, get, post, put, web, HttpResponse};
+use core::{api_key_context::ApiKeyContext, error::Error};
+use types::enums::ApiKeyScope;
+use service::{
+    notification_service::NotificationService,
+    notification_template_service::NotificationTemplateService
+};
+use crate::{
+    middlewares::require_scope::RequireScope,
+    dto::{
+        pagination_dto::PaginationDto,
+        template_dto::{
+            CreateTemplateDto, PaginatedTemplateDto, PreviewTemplateDto, TemplateDto,
+            TemplateIdPathParameterDto, TemplateKeyPathParameterDto, TemplatePreviewDto, UpdateTemplateDto
+        }
+    },
+};
+
+#[utoipa::path(
+    path = "/templates",
+    tag = "Templates",
+    responses(
+        (status = 201, description = "Template is created", body = TemplateDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[post("", wrap = "RequireScope(ApiKeyScope::Admin)")]
+pub async fn create_template(
+    mut dto: web::Json<CreateTemplateDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    dto.validate()?;
+
+    let mut notification_template_service = NotificationTemplateService::new();
+    let template = notification_template_service.create(
+        api_key_context.app_id,
+        dto.key.clone(),
+        dto.locale.clone(),
+        dto.get_notification_template()
+    ).await?;
+
+    Ok(HttpResponse::Created().json(TemplateDto::from(&template)))
+}
+
+#[utoipa::path(
+    path = "/templates",
+    tag = "Templates",
+    responses(
+        (status = 200, description = "List of templates paginated", body = PaginatedTemplateDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[get("", wrap = "RequireScope(ApiKeyScope::Read)")]
+pub async fn fetch_template_list_paginated(
+    mut pagination_info: web::Query<PaginationDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    pagination_info.validate()?;
+
+    let page = pagination_info.get_offset();
+    let max_result = pagination_info.get_limit();
+
+    let mut notification_template_service = NotificationTemplateService::new();
+    let list_of_templates = notification_template_service.fetch_template_paginated(api_key_context.app_id, page * max_result, max_result).await?;
+    let total_count = notification_template_service.count(api_key_context.app_id).await?;
+
+    let total_page = (total_count + max_result - 1) / max_result;
+    let next_page: Option<i32> = if page + 1 < total_page { Option::from(page + 2) } else { None };
+    let previous_page: Option<i32> = if page > 0 { Option::from(page) } else { None };
+
+    Ok(HttpResponse::Ok().json(
+        PaginatedTemplateDto {
+            total_count,
+            total_page,
+            next_page,
+            previous_page,
+            templates: list_of_templates.iter().map(|t| t.into()).collect()
+        }
+    ))
+}
+
+#[utoipa::path(
+    path = "/templates/{template_id}",
+    tag = "Templates",
+    responses(
+        (status = 200, description = "Template", body = TemplateDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "Template not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[get("/{template_id}", wrap = "RequireScope(ApiKeyScope::Read)")]
+pub async fn fetch_template(
+    mut params: web::Path<TemplateIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    let template_id = params.get_id_or_error()?;
+
+    let mut notification_template_service = NotificationTemplateService::new();
+    let template = notification_template_service.find_template_by_id(api_key_context.app_id, template_id.into()).await?;
+
+    Ok(HttpResponse::Ok().json(TemplateDto::from(&template)))
+}
+
+#[utoipa::path(
+    path = "/templates/{template_id}",
+    tag = "Templates",
+    responses(
+        (status = 200, description = "Template is updated and its version incremented", body = TemplateDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "Template not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[put("/{template_id}", wrap = "RequireScope(ApiKeyScope::Admin)")]
+pub async fn update_template(
+    mut params: web::Path<TemplateIdPathParameterDto>,
+    mut dto: web::Json<UpdateTemplateDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    let template_id = params.get_id_or_error()?;
+    dto.validate()?;
+
+    let mut notification_template_service = NotificationTemplateService::new();
+    let template = notification_template_service.update(api_key_context.app_id, template_id.into(), dto.get_notification_template()).await?;
+
+    Ok(HttpResponse::Ok().json(TemplateDto::from(&template)))
+}
+
+#[utoipa::path(
+    path = "/templates/{template_id}",
+    tag = "Templates",
+    responses(
+        (status = 204, description = "Template is deleted", body = String, content_type = "text/plain"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "Template not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[delete("/{template_id}", wrap = "RequireScope(ApiKeyScope::Admin)")]
+pub async fn delete_template(
+    mut params: web::Path<TemplateIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    let template_id = params.get_id_or_error()?;
+
+    let mut notification_template_service = NotificationTemplateService::new();
+    notification_template_service.delete(api_key_context.app_id, template_id.into()).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+#[utoipa::path(
+    path = "/templates/{key}/preview",
+    tag = "Templates",
+    responses(
+        (status = 200, description = "Rendered template and the provider message it would send", body = TemplatePreviewDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[post("/{key}/preview", wrap = "RequireScope(ApiKeyScope::Read)")]
+pub async fn preview_template(
+    params: web::Path<TemplateKeyPathParameterDto>,
+    mut dto: web::Json<PreviewTemplateDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    dto.validate()?;
+
+    let mut notification_template_service = NotificationTemplateService::new();
+    notification_template_service.validate_template_data(api_key_context.app_id, &params.key, &dto.template_data).await?;
+    let information = notification_template_service.get_rendered_notification_information(
+        api_key_context.app_id,
+        &params.key,
+        &dto.get_locale(),
+        &dto.get_device_type(),
+        &dto.template_data,
+        &dto.get_notification_options()
+    ).await?;
+
+    let message = NotificationService::get_message(
+        &dto.get_device_token().into(),
+        &dto.get_device_type(),
+        &information,
+        &dto.extra_data
+    );
+    let payload_size = NotificationService::get_payload_size(&dto.get_device_type(), &message);
+    let message = match serde_json::to_value(message) {
+        Ok(message) => message,
+        Err(err) => return Err(Error::ProviderError(err.to_string()))
+    };
+
+    Ok(HttpResponse::Ok().json(TemplatePreviewDto::new(&information, payload_size, message)))
+}
+
+pub fn web_templates() -> actix_web::Scope {
+    web::scope("/templates")
+        .service(create_template)
+        .service(fetch_template_list_paginated)
+        .service(fetch_template)
+        .service(update_template)
+        .service(delete_template)
+        .service(preview_template)
+}
\ No newline at end of file
@@ -1,13 +1,22 @@
 use actix_web::{get, post, put, web, HttpRequest, HttpResponse};
-use core::{http_helper::get_user_context, error::Error};
-use service::user_service::UserService;
+use core::{api_key_context::ApiKeyContext, http_helper::get_user_context, error::Error};
+use types::enums::{ApiKeyScope, DeviceType};
+use service::{
+    campaign_service::CampaignService,
+    notification_template_service::NotificationTemplateService,
+    user_service::UserService
+};
 use crate::{
+    middlewares::{idempotent::Idempotent, require_scope::RequireScope},
     dto::{
         pagination_dto::PaginationDto,
+        campaign_dto::CampaignDto,
         user_dto::{
             PaginatedUserDto, SendUserNotificationDto,
             UpdateUserTokenDto, SendUsersNotificationDto,
-            UserIdPathParameterDto
+            UserIdPathParameterDto, SendAudienceNotificationDto,
+            UserAttributesDto,
+            UserPreferencesDto
         }
     },
 };
@@ -18,18 +27,78 @@ use crate::{
     responses(
         (status = 204, description = "User token is updated", body = String, content_type = "text/plain"),
         (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 401, description = "Missing, invalid or expired device signature", body = ErrorResponse, content_type = "application/json"),
+        (status = 403, description = "Device registration secret is not configured", body = ErrorResponse, content_type = "application/json"),
+        (status = 429, description = "Rate limit exceeded, see the Retry-After header", body = ErrorResponse, content_type = "application/json"),
         (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
     )
 )]
-#[put("")]
+#[put("", wrap = "RequireScope(ApiKeyScope::Register)")]
 pub async fn update_user_token(
-    dto: web::Json<UpdateUserTokenDto>,
+    mut dto: web::Json<UpdateUserTokenDto>,
     req: HttpRequest
 ) -> Result<HttpResponse, Error> {
     let user_context = get_user_context(req)?;
+    dto.validate()?;
+
+    let mut user_service = UserService::new();
+    user_service.update_user_token_service(
+        &user_context,
+        dto.clone().token.into(),
+        dto.get_device_settings()?
+    ).await?;
+    if let Some(user_attributes) = dto.get_user_attributes() {
+        user_service.update_device_attributes(&user_context, user_attributes).await?;
+    }
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
+#[utoipa::path(
+    path = "/users/{user_id}/attributes",
+    tag = "Users",
+    responses(
+        (status = 200, description = "Tags and attributes of the user", body = UserAttributesDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "User not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 429, description = "Rate limit exceeded, see the Retry-After header", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[get("/{user_id}/attributes", wrap = "RequireScope(ApiKeyScope::Read)")]
+pub async fn fetch_user_attributes(
+    mut params: web::Path<UserIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    let user_id = params.get_id_or_error()?;
+
+    let mut user_service = UserService::new();
+    let user_attributes = user_service.find_user_attributes(api_key_context.app_id, user_id.into()).await?;
+
+    Ok(HttpResponse::Ok().json(UserAttributesDto::from(user_attributes)))
+}
+
+#[utoipa::path(
+    path = "/users/{user_id}/attributes",
+    tag = "Users",
+    responses(
+        (status = 204, description = "Tags and attributes are replaced", body = String, content_type = "text/plain"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "User not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 429, description = "Rate limit exceeded, see the Retry-After header", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[put("/{user_id}/attributes", wrap = "RequireScope(ApiKeyScope::Send)")]
+pub async fn update_user_attributes(
+    dto: web::Json<UserAttributesDto>,
+    mut params: web::Path<UserIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    let user_id = params.get_id_or_error()?;
 
     let mut user_service = UserService::new();
-    user_service.update_user_token_service(&user_context, dto.clone().token.into()).await?;
+    user_service.update_user_attributes(api_key_context.app_id, user_id.into(), (&dto.into_inner()).into()).await?;
 
     Ok(HttpResponse::NoContent().body(""))
 }
@@ -40,22 +109,39 @@ pub async fn update_user_token(
     responses(
         (status = 201, description = "Notification is sent", body = String, content_type = "text/plain"),
         (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 409, description = "Idempotency-Key reused with a different request, or still in progress", body = ErrorResponse, content_type = "application/json"),
+        (status = 429, description = "Rate limit exceeded, see the Retry-After header", body = ErrorResponse, content_type = "application/json"),
         (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
     )
 )]
-#[post("/{user_id}/notifications")]
+#[post("/{user_id}/notifications", wrap = "Idempotent", wrap = "RequireScope(ApiKeyScope::Send)")]
 pub async fn send_user_notification(
-    dto: web::Json<SendUserNotificationDto>,
-    mut params: web::Path<UserIdPathParameterDto>
+    mut dto: web::Json<SendUserNotificationDto>,
+    mut params: web::Path<UserIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
 ) -> Result<HttpResponse, Error> {
     let user_id = params.get_id_or_error()?;
+    dto.validate()?;
+
+    let mut notification_template_service = NotificationTemplateService::new();
+    notification_template_service.validate_template_data(api_key_context.app_id, &dto.notification_type, &dto.template_data).await?;
+    notification_template_service.validate_user_payload_size(
+        api_key_context.app_id,
+        user_id.into(),
+        &dto.notification_type,
+        &dto.template_data,
+        &dto.extra_data,
+        &dto.get_notification_options()
+    ).await?;
 
     let mut user_service = UserService::new();
     user_service.send_user_notification(
+        api_key_context.app_id,
         user_id.into(),
-        dto.clone().notification_type.into(),
+        dto.clone().notification_type,
         dto.clone().extra_data,
-        dto.clone().template_data
+        dto.clone().template_data,
+        dto.get_notification_options()
     ).await?;
 
     Ok(HttpResponse::Created().body(""))
@@ -67,12 +153,14 @@ pub async fn send_user_notification(
     responses(
         (status = 200, description = "List of users paginated", body = PaginatedUserDto, content_type = "application/json"),
         (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 429, description = "Rate limit exceeded, see the Retry-After header", body = ErrorResponse, content_type = "application/json"),
         (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
     )
 )]
-#[get("")]
+#[get("", wrap = "RequireScope(ApiKeyScope::Read)")]
 pub async fn fetch_user_list_paginated(
-    mut pagination_info: web::Query<PaginationDto>
+    mut pagination_info: web::Query<PaginationDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
 ) -> Result<HttpResponse, Error> {
     pagination_info.validate()?;
 
@@ -80,8 +168,8 @@ pub async fn fetch_user_list_paginated(
     let max_result = pagination_info.get_limit();
 
     let mut user_service = UserService::new();
-    let list_of_users = user_service.fetch_user_paginated(offset, max_result).await?;
-    let total_count = user_service.count().await?;
+    let list_of_users = user_service.fetch_user_paginated(api_key_context.app_id, offset, max_result).await?;
+    let total_count = user_service.count(api_key_context.app_id).await?;
 
     let dvs: f32 = (total_count / max_result) as f32;
     let total_page = dvs.ceil() as i32;
@@ -106,25 +194,127 @@ pub async fn fetch_user_list_paginated(
     responses(
         (status = 201, description = "Created", body = String, content_type = "plain/text"),
         (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "Some users do not exist, nothing is enqueued", body = ErrorResponse, content_type = "application/json"),
+        (status = 409, description = "Idempotency-Key reused with a different request, or still in progress", body = ErrorResponse, content_type = "application/json"),
+        (status = 429, description = "Rate limit exceeded, see the Retry-After header", body = ErrorResponse, content_type = "application/json"),
         (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
     )
 )]
-#[post("/notifications")]
+#[post("/notifications", wrap = "Idempotent", wrap = "RequireScope(ApiKeyScope::Send)")]
 pub async fn send_users_notifications(
-    mut dto: web::Json<SendUsersNotificationDto>
+    mut dto: web::Json<SendUsersNotificationDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
 ) -> Result<HttpResponse, Error> {
     dto.validate()?;
 
+    let mut notification_template_service = NotificationTemplateService::new();
+    notification_template_service.validate_users_template_data(api_key_context.app_id, &dto.notification_type, &dto.users).await?;
+    notification_template_service.validate_users_payload_size(api_key_context.app_id, &dto.notification_type, &dto.users).await?;
+
     let mut user_service = UserService::new();
-    user_service.send_users_notification(dto.clone().users, dto.clone().notification_type.into()).await?;
+    user_service.send_users_notification(api_key_context.app_id, dto.clone().users, dto.clone().notification_type, dto.get_rate()).await?;
 
     Ok(HttpResponse::Created().body(""))
 }
 
+#[utoipa::path(
+    path = "/users/notifications/audience",
+    tag = "Users",
+    responses(
+        (status = 202, description = "A campaign enqueues a notification for every user matching the audience", body = CampaignDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 409, description = "Idempotency-Key reused with a different request, or still in progress", body = ErrorResponse, content_type = "application/json"),
+        (status = 429, description = "Rate limit exceeded, see the Retry-After header", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[post("/notifications/audience", wrap = "Idempotent", wrap = "RequireScope(ApiKeyScope::Send)")]
+pub async fn send_audience_notification(
+    mut dto: web::Json<SendAudienceNotificationDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    dto.validate()?;
+
+    let mut notification_template_service = NotificationTemplateService::new();
+    notification_template_service.validate_template_data(api_key_context.app_id, &dto.notification_type, &dto.template_data).await?;
+    // The audience may target both platforms, so the payload must fit every one of them.
+    for device_type in [DeviceType::Android, DeviceType::IOS] {
+        notification_template_service.validate_payload_size(
+            api_key_context.app_id,
+            &dto.notification_type,
+            &None,
+            &device_type,
+            &dto.template_data,
+            &dto.extra_data,
+            &dto.get_notification_options()
+        ).await?;
+    }
+
+    // Large audiences would outlive the request, the consumer fans the notification out by chunks instead.
+    let mut campaign_service = CampaignService::new();
+    let campaign = campaign_service.launch(api_key_context.app_id, dto.get_notification_campaign()).await?;
+
+    Ok(HttpResponse::Accepted().json(CampaignDto::from(&campaign)))
+}
+
+#[utoipa::path(
+    path = "/users/{user_id}/preferences",
+    tag = "Users",
+    responses(
+        (status = 200, description = "Preferences of the user for every category and channel", body = UserPreferencesDto, content_type = "application/json"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "User not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 429, description = "Rate limit exceeded, see the Retry-After header", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[get("/{user_id}/preferences", wrap = "RequireScope(ApiKeyScope::Read)")]
+pub async fn fetch_user_preferences(
+    mut params: web::Path<UserIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    let user_id = params.get_id_or_error()?;
+
+    let mut user_service = UserService::new();
+    let preferences = user_service.find_user_preferences(api_key_context.app_id, user_id.into()).await?;
+
+    Ok(HttpResponse::Ok().json(UserPreferencesDto::from(preferences)))
+}
+
+#[utoipa::path(
+    path = "/users/{user_id}/preferences",
+    tag = "Users",
+    responses(
+        (status = 204, description = "Given preferences are saved", body = String, content_type = "text/plain"),
+        (status = 400, description = "Bad request", body = ErrorResponse, content_type = "application/json"),
+        (status = 404, description = "User not found", body = ErrorResponse, content_type = "application/json"),
+        (status = 429, description = "Rate limit exceeded, see the Retry-After header", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Internal server error", body = ErrorResponse, content_type = "application/json")
+    )
+)]
+#[put("/{user_id}/preferences", wrap = "RequireScope(ApiKeyScope::Send)")]
+pub async fn update_user_preferences(
+    dto: web::Json<UserPreferencesDto>,
+    mut params: web::Path<UserIdPathParameterDto>,
+    api_key_context: web::ReqData<ApiKeyContext>
+) -> Result<HttpResponse, Error> {
+    let user_id = params.get_id_or_error()?;
+
+    let mut user_service = UserService::new();
+    user_service.update_user_preferences(api_key_context.app_id, user_id.into(), dto.get_user_preferences()?).await?;
+
+    Ok(HttpResponse::NoContent().body(""))
+}
+
 pub fn web_users() -> actix_web::Scope {
     web::scope("/users")
         .service(update_user_token)
         .service(send_user_notification)
         .service(fetch_user_list_paginated)
         .service(send_users_notifications)
+        .service(send_audience_notification)
+        .service(fetch_user_attributes)
+        .service(update_user_attributes)
+        .service(fetch_user_preferences)
+        .service(update_user_preferences)
 }
\ No newline at end of file
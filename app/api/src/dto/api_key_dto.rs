@@ -0,0 +1,96 @@
+use std::str::FromStr;
+use serde::{Serialize, Deserialize};
+use service::rows::api_key_from_row::ApiKeyFromRow;
+use types::enums::ApiKeyScope;
+use utoipa::ToSchema;
+use core::error::Error;
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct CreateApiKeyDto {
+    pub name: String,
+    pub scopes: Vec<String>
+}
+
+impl CreateApiKeyDto {
+    pub fn validate(&mut self) -> Result<&mut Self, Error> {
+        if self.name.trim().is_empty() {
+            return Err(Error::ValidationError("name cannot be empty".to_string()))
+        }
+        if self.scopes.is_empty() {
+            return Err(Error::ValidationError("scopes cannot be empty".to_string()))
+        }
+        self.get_scopes()?;
+
+        Ok(self)
+    }
+
+    pub fn get_scopes(&self) -> Result<Vec<ApiKeyScope>, Error> {
+        let mut scopes: Vec<ApiKeyScope> = Vec::from([]);
+        for scope in &self.scopes {
+            match ApiKeyScope::from_str(scope) {
+                Ok(scope) if !scopes.contains(&scope) => scopes.push(scope),
+                Ok(_) => {},
+                Err(_) => return Err(Error::ValidationError(format!("Unknown scope \"{}\", expected register, send, read or admin", scope)))
+            }
+        }
+
+        Ok(scopes)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct ApiKeyDto {
+    pub id: i32,
+    pub app_id: i32,
+    pub name: String,
+    pub key_prefix: String,
+    pub scopes: Vec<String>,
+    pub last_used_date: Option<String>,
+    pub revocation_date: Option<String>,
+    pub creation_date: String
+}
+
+impl From<&ApiKeyFromRow> for ApiKeyDto {
+    fn from(value: &ApiKeyFromRow) -> Self {
+        ApiKeyDto {
+            id: value.id,
+            app_id: value.app_id,
+            name: value.name.clone(),
+            key_prefix: value.key_prefix.clone(),
+            scopes: value.scopes.0.clone(),
+            last_used_date: value.last_used_date.map(|date| date.to_rfc3339()),
+            revocation_date: value.revocation_date.map(|date| date.to_rfc3339()),
+            creation_date: value.creation_date.to_rfc3339()
+        }
+    }
+}
+
+// Only returned on creation, the plain key is not stored.
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct CreatedApiKeyDto {
+    pub key: String,
+    pub api_key: ApiKeyDto
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct PaginatedApiKeyDto {
+    pub total_count: i32,
+    pub total_page: i32,
+    pub next_page: Option<i32>,
+    pub previous_page: Option<i32>,
+    pub api_keys: Vec<ApiKeyDto>
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ApiKeyIdPathParameterDto {
+    pub api_key_id: String
+}
+
+impl ApiKeyIdPathParameterDto {
+    pub fn get_id_or_error(&mut self) -> Result<i32, Error> {
+        match self.api_key_id.parse::<i32>() {
+            Ok(id) => Ok(id),
+            Err(_) => Err(Error::ValidationError("api_key_id must be integer".to_string()))
+        }
+    }
+}
\ No newline at end of file
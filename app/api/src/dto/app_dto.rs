@@ -0,0 +1,117 @@
+use serde::{Serialize, Deserialize};
+use service::{
+    app_service::NotificationApp,
+    rows::app_from_row::AppFromRow
+};
+use utoipa::ToSchema;
+use core::error::Error;
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct CreateAppDto {
+    pub name: String,
+    pub google_service_account_credentials_path: Option<String>,
+    pub notification_resources_path: Option<String>,
+    pub device_registration_secret: Option<String>
+}
+
+impl CreateAppDto {
+    pub fn validate(&mut self) -> Result<&mut Self, Error> {
+        if self.name.trim().is_empty() {
+            return Err(Error::ValidationError("name cannot be empty".to_string()))
+        }
+
+        Ok(self)
+    }
+
+    pub fn get_notification_app(&self) -> NotificationApp {
+        NotificationApp {
+            name: self.name.trim().to_string(),
+            google_service_account_credentials_path: self.google_service_account_credentials_path.clone(),
+            notification_resources_path: self.notification_resources_path.clone(),
+            device_registration_secret: self.device_registration_secret.clone(),
+            clear_device_registration_secret: false
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct UpdateAppDto {
+    pub name: String,
+    pub google_service_account_credentials_path: Option<String>,
+    pub notification_resources_path: Option<String>,
+    // The stored secret is kept when absent.
+    pub device_registration_secret: Option<String>,
+    #[serde(default)]
+    pub clear_device_registration_secret: bool
+}
+
+impl UpdateAppDto {
+    pub fn validate(&mut self) -> Result<&mut Self, Error> {
+        if self.name.trim().is_empty() {
+            return Err(Error::ValidationError("name cannot be empty".to_string()))
+        }
+
+        if self.clear_device_registration_secret && self.device_registration_secret.is_some() {
+            return Err(Error::ValidationError("device_registration_secret cannot be set while clearing it".to_string()))
+        }
+
+        Ok(self)
+    }
+
+    pub fn get_notification_app(&self) -> NotificationApp {
+        NotificationApp {
+            name: self.name.trim().to_string(),
+            google_service_account_credentials_path: self.google_service_account_credentials_path.clone(),
+            notification_resources_path: self.notification_resources_path.clone(),
+            device_registration_secret: self.device_registration_secret.clone(),
+            clear_device_registration_secret: self.clear_device_registration_secret
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct AppDto {
+    pub id: i32,
+    pub name: String,
+    pub google_service_account_credentials_path: Option<String>,
+    pub notification_resources_path: Option<String>,
+    // The secret itself is never returned.
+    pub has_device_registration_secret: bool,
+    pub creation_date: String
+}
+
+impl From<&AppFromRow> for AppDto {
+    fn from(value: &AppFromRow) -> Self {
+        AppDto {
+            id: value.id,
+            name: value.name.clone(),
+            google_service_account_credentials_path: value.google_service_account_credentials_path.clone(),
+            notification_resources_path: value.notification_resources_path.clone(),
+            has_device_registration_secret: value.device_registration_secret.is_some(),
+            creation_date: value.creation_date.to_rfc3339()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct PaginatedAppDto {
+    pub total_count: i32,
+    pub total_page: i32,
+    pub next_page: Option<i32>,
+    pub previous_page: Option<i32>,
+    pub apps: Vec<AppDto>
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AppIdPathParameterDto {
+    pub app_id: String
+}
+
+impl AppIdPathParameterDto {
+    pub fn get_id_or_error(&mut self) -> Result<i32, Error> {
+        match self.app_id.parse::<i32>() {
+            Ok(id) => Ok(id),
+            Err(_) => Err(Error::ValidationError("app_id must be integer".to_string()))
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use service::{
+    audience::Audience,
+    campaign_service::{CampaignProgress, NotificationCampaign},
+    notification_options::NotificationOptions,
+    notification_rate::NotificationRate,
+    rows::campaign_from_row::CampaignFromRow
+};
+use types::extra_data::ExtraData;
+use utoipa::ToSchema;
+use core::error::Error;
+use crate::dto::{
+    notification_options_dto::NotificationOptionsDto,
+    notification_rate_dto::NotificationRateDto
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct CreateCampaignDto {
+    pub name: String,
+    pub notification_type: String,
+    pub audience: Option<String>,
+    #[schema(value_type = Option<Object>)]
+    pub extra_data: Option<ExtraData>,
+    pub template_data: Option<HashMap<String, String>>,
+    pub options: Option<NotificationOptionsDto>,
+    pub rate: Option<NotificationRateDto>,
+    pub scheduled_date: Option<String>
+}
+
+impl CreateCampaignDto {
+    pub fn validate(&mut self) -> Result<&mut Self, Error> {
+        if self.name.trim().is_empty() {
+            return Err(Error::ValidationError("name cannot be empty".to_string()))
+        }
+        if let Some(audience) = &self.audience {
+            Audience::parse(audience)?;
+        }
+        if let Some(violation) = self.extra_data.as_ref().and_then(|extra_data| extra_data.get_violation()) {
+            return Err(Error::ValidationError(violation))
+        }
+        self.get_notification_options().validate()?;
+        if let Some(rate) = self.get_rate() {
+            rate.validate()?;
+        }
+        self.get_scheduled_date()?;
+
+        Ok(self)
+    }
+
+    pub fn get_notification_options(&self) -> NotificationOptions {
+        self.options.as_ref().map(NotificationOptions::from).unwrap_or_default()
+    }
+
+    pub fn get_rate(&self) -> Option<NotificationRate> {
+        self.rate.as_ref().map(NotificationRate::from)
+    }
+
+    pub fn get_scheduled_date(&self) -> Result<Option<DateTime<Utc>>, Error> {
+        match &self.scheduled_date {
+            Some(scheduled_date) => match DateTime::parse_from_rfc3339(scheduled_date) {
+                Ok(scheduled_date) => Ok(Some(scheduled_date.with_timezone(&Utc))),
+                Err(_) => Err(Error::ValidationError("scheduled_date must be a RFC 3339 date".to_string()))
+            },
+            None => Ok(None)
+        }
+    }
+
+    pub fn get_notification_campaign(&self) -> Result<NotificationCampaign, Error> {
+        Ok(NotificationCampaign {
+            name: self.name.clone(),
+            notification_type: self.notification_type.clone(),
+            audience: self.audience.clone(),
+            extra_data: self.extra_data.clone(),
+            template_data: self.template_data.clone(),
+            options: self.get_notification_options(),
+            rate: self.get_rate(),
+            scheduled_date: self.get_scheduled_date()?
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct CampaignProgressDto {
+    pub queued: i32,
+    pub sent: i32,
+    pub failed: i32,
+    pub canceled: i32,
+    pub pending: i32
+}
+
+impl From<&CampaignProgress> for CampaignProgressDto {
+    fn from(value: &CampaignProgress) -> Self {
+        CampaignProgressDto {
+            queued: value.queued,
+            sent: value.sent,
+            failed: value.failed,
+            canceled: value.canceled,
+            pending: value.pending
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct CampaignDto {
+    pub id: i32,
+    pub name: String,
+    pub notification_type: String,
+    pub audience: Option<String>,
+    #[schema(value_type = Option<Object>)]
+    pub extra_data: Option<ExtraData>,
+    pub template_data: Option<HashMap<String, String>>,
+    pub options: NotificationOptionsDto,
+    pub rate: Option<NotificationRateDto>,
+    pub status: String,
+    pub scheduled_date: Option<String>,
+    pub start_date: Option<String>,
+    pub completion_date: Option<String>,
+    pub creation_date: String,
+    pub progress: Option<CampaignProgressDto>
+}
+
+impl CampaignDto {
+    pub fn with_progress(mut self, progress: &CampaignProgress) -> Self {
+        self.progress = Some(progress.into());
+
+        self
+    }
+}
+
+impl From<&CampaignFromRow> for CampaignDto {
+    fn from(value: &CampaignFromRow) -> Self {
+        CampaignDto {
+            id: value.id,
+            name: value.name.clone(),
+            notification_type: value.notification_type.clone(),
+            audience: value.audience.clone(),
+            extra_data: value.extra_data.as_ref().map(|extra_data| extra_data.0.clone()),
+            template_data: value.template_data.as_ref().map(|template_data| template_data.0.clone()),
+            options: (&value.get_options()).into(),
+            rate: value.rate.as_ref().map(|rate| (&rate.0).into()),
+            status: value.status.clone(),
+            scheduled_date: value.scheduled_date.map(|date| date.to_rfc3339()),
+            start_date: value.start_date.map(|date| date.to_rfc3339()),
+            completion_date: value.completion_date.map(|date| date.to_rfc3339()),
+            creation_date: value.creation_date.to_rfc3339(),
+            progress: None
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct PaginatedCampaignDto {
+    pub total_count: i32,
+    pub total_page: i32,
+    pub next_page: Option<i32>,
+    pub previous_page: Option<i32>,
+    pub campaigns: Vec<CampaignDto>
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CampaignIdPathParameterDto {
+    pub campaign_id: String
+}
+
+impl CampaignIdPathParameterDto {
+    pub fn get_id_or_error(&mut self) -> Result<i32, Error> {
+        match self.campaign_id.parse::<i32>() {
+            Ok(id) => Ok(id),
+            Err(_) => Err(Error::ValidationError("campaign_id must be integer".to_string()))
+        }
+    }
+}
\ No newline at end of file
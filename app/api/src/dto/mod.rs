@@ -1,2 +1,9 @@
 pub mod user_dto;
-pub mod pagination_dto;
\ No newline at end of file
+pub mod pagination_dto;
+pub mod schedule_dto;
+pub mod template_dto;
+pub mod notification_options_dto;
+pub mod campaign_dto;
+pub mod notification_rate_dto;
+pub mod api_key_dto;
+pub mod app_dto;
\ No newline at end of file
@@ -0,0 +1,91 @@
+use serde::{Serialize, Deserialize};
+use service::notification_options::{
+    AndroidNotificationOptions,
+    IosNotificationOptions,
+    NotificationOptions
+};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, ToSchema)]
+pub struct NotificationOptionsDto {
+    pub silent: Option<bool>,
+    pub priority: Option<String>,
+    pub ttl: Option<i64>,
+    pub collapse_key: Option<String>,
+    pub image: Option<String>,
+    pub sound: Option<String>,
+    pub badge: Option<i32>,
+    pub click_action: Option<String>,
+    pub dedup_key: Option<String>,
+    pub deduplicate: Option<bool>,
+    pub dedup_window: Option<i64>,
+    pub android: Option<AndroidNotificationOptionsDto>,
+    pub ios: Option<IosNotificationOptionsDto>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct AndroidNotificationOptionsDto {
+    pub channel_id: Option<String>,
+    pub icon: Option<String>,
+    pub color: Option<String>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct IosNotificationOptionsDto {
+    pub category: Option<String>,
+    pub thread_id: Option<String>
+}
+
+impl From<&NotificationOptionsDto> for NotificationOptions {
+    fn from(value: &NotificationOptionsDto) -> Self {
+        NotificationOptions {
+            silent: value.silent,
+            priority: value.priority.clone(),
+            ttl: value.ttl,
+            collapse_key: value.collapse_key.clone(),
+            image: value.image.clone(),
+            sound: value.sound.clone(),
+            badge: value.badge,
+            click_action: value.click_action.clone(),
+            dedup_key: value.dedup_key.clone(),
+            deduplicate: value.deduplicate,
+            dedup_window: value.dedup_window,
+            android: value.android.as_ref().map(|android| AndroidNotificationOptions {
+                channel_id: android.channel_id.clone(),
+                icon: android.icon.clone(),
+                color: android.color.clone()
+            }),
+            ios: value.ios.as_ref().map(|ios| IosNotificationOptions {
+                category: ios.category.clone(),
+                thread_id: ios.thread_id.clone()
+            })
+        }
+    }
+}
+
+impl From<&NotificationOptions> for NotificationOptionsDto {
+    fn from(value: &NotificationOptions) -> Self {
+        NotificationOptionsDto {
+            silent: value.silent,
+            priority: value.priority.clone(),
+            ttl: value.ttl,
+            collapse_key: value.collapse_key.clone(),
+            image: value.image.clone(),
+            sound: value.sound.clone(),
+            badge: value.badge,
+            click_action: value.click_action.clone(),
+            dedup_key: value.dedup_key.clone(),
+            deduplicate: value.deduplicate,
+            dedup_window: value.dedup_window,
+            android: value.android.as_ref().map(|android| AndroidNotificationOptionsDto {
+                channel_id: android.channel_id.clone(),
+                icon: android.icon.clone(),
+                color: android.color.clone()
+            }),
+            ios: value.ios.as_ref().map(|ios| IosNotificationOptionsDto {
+                category: ios.category.clone(),
+                thread_id: ios.thread_id.clone()
+            })
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,27 @@
+use serde::{Serialize, Deserialize};
+use service::notification_rate::NotificationRate;
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct NotificationRateDto {
+    pub messages: i32,
+    pub per: String
+}
+
+impl From<&NotificationRateDto> for NotificationRate {
+    fn from(value: &NotificationRateDto) -> Self {
+        NotificationRate {
+            messages: value.messages,
+            per: value.per.clone()
+        }
+    }
+}
+
+impl From<&NotificationRate> for NotificationRateDto {
+    fn from(value: &NotificationRate) -> Self {
+        NotificationRateDto {
+            messages: value.messages,
+            per: value.per.clone()
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+use service::{
+    audience::Audience,
+    rows::schedule_from_row::ScheduleFromRow,
+    schedule_service::NotificationSchedule
+};
+use types::extra_data::ExtraData;
+use utoipa::ToSchema;
+use core::error::Error;
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct CreateScheduleDto {
+    pub cron_expression: String,
+    pub timezone: Option<String>,
+    pub notification_type: String,
+    pub user_ids: Option<Vec<i32>>,
+    pub audience: Option<String>,
+    #[schema(value_type = Option<Object>)]
+    pub extra_data: Option<ExtraData>,
+    pub template_data: Option<HashMap<String, String>>
+}
+
+impl CreateScheduleDto {
+    const DEFAULT_TIMEZONE: &'static str = "UTC";
+
+    pub fn validate(&mut self) -> Result<&mut Self, Error> {
+        if let Some(user_ids) = &self.user_ids {
+            if user_ids.is_empty() {
+                return Err(Error::ValidationError("user_ids cannot be empty".to_string()))
+            }
+            if self.audience.is_some() {
+                return Err(Error::ValidationError("user_ids and audience cannot be used together".to_string()))
+            }
+        }
+        if let Some(audience) = &self.audience {
+            Audience::parse(audience)?;
+        }
+        if let Some(violation) = self.extra_data.as_ref().and_then(|extra_data| extra_data.get_violation()) {
+            return Err(Error::ValidationError(violation))
+        }
+
+        Ok(self)
+    }
+
+    pub fn get_notification_schedule(&self) -> NotificationSchedule {
+        NotificationSchedule {
+            cron_expression: self.cron_expression.clone(),
+            timezone: self.timezone.clone().unwrap_or(Self::DEFAULT_TIMEZONE.to_string()),
+            notification_type: self.notification_type.clone(),
+            user_ids: self.user_ids.clone(),
+            audience: self.audience.clone(),
+            extra_data: self.extra_data.clone(),
+            template_data: self.template_data.clone()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct ScheduleDto {
+    pub id: i32,
+    pub cron_expression: String,
+    pub timezone: String,
+    pub notification_type: String,
+    pub user_ids: Option<Vec<i32>>,
+    pub audience: Option<String>,
+    #[schema(value_type = Option<Object>)]
+    pub extra_data: Option<ExtraData>,
+    pub template_data: Option<HashMap<String, String>>,
+    pub is_paused: bool,
+    pub next_run_date: String,
+    pub last_run_date: Option<String>,
+    pub creation_date: String
+}
+
+impl From<&ScheduleFromRow> for ScheduleDto {
+    fn from(value: &ScheduleFromRow) -> Self {
+        ScheduleDto {
+            id: value.id,
+            cron_expression: value.cron_expression.clone(),
+            timezone: value.timezone.clone(),
+            notification_type: value.notification_type.clone(),
+            user_ids: value.user_ids.as_ref().map(|user_ids| user_ids.0.clone()),
+            audience: value.audience.clone(),
+            extra_data: value.extra_data.as_ref().map(|extra_data| extra_data.0.clone()),
+            template_data: value.template_data.as_ref().map(|template_data| template_data.0.clone()),
+            is_paused: value.is_paused,
+            next_run_date: value.next_run_date.to_rfc3339(),
+            last_run_date: value.last_run_date.map(|date| date.to_rfc3339()),
+            creation_date: value.creation_date.to_rfc3339()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct PaginatedScheduleDto {
+    pub total_count: i32,
+    pub total_page: i32,
+    pub next_page: Option<i32>,
+    pub previous_page: Option<i32>,
+    pub schedules: Vec<ScheduleDto>
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScheduleIdPathParameterDto {
+    pub schedule_id: String
+}
+
+impl ScheduleIdPathParameterDto {
+    pub fn get_id_or_error(&mut self) -> Result<i32, Error> {
+        match self.schedule_id.parse::<i32>() {
+            Ok(id) => Ok(id),
+            Err(_) => Err(Error::ValidationError("schedule_id must be integer".to_string()))
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,309 @@
+use std::{
+    collections::HashMap,
+    str::FromStr
+};
+use serde::{Serialize, Deserialize};
+use service::{
+    notification_options::NotificationOptions,
+    notification_template_service::{DigestInformation, NotificationInformation, NotificationTemplate, PlatformNotificationInformation},
+    rows::notification_template_from_row::NotificationTemplateFromRow
+};
+use types::{
+    extra_data::ExtraData,
+    enums::DeviceType,
+    locale::Locale
+};
+use utoipa::ToSchema;
+use core::error::Error;
+use crate::dto::notification_options_dto::NotificationOptionsDto;
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct PlatformOverrideDto {
+    pub title: Option<String>,
+    pub body: Option<String>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct DigestDto {
+    pub title: String,
+    pub body: String,
+    pub window: Option<i64>,
+    pub max_count: Option<i64>
+}
+
+impl From<&DigestInformation> for DigestDto {
+    fn from(value: &DigestInformation) -> Self {
+        DigestDto {
+            title: value.title.clone(),
+            body: value.body.clone(),
+            window: value.window,
+            max_count: value.max_count
+        }
+    }
+}
+
+impl From<&DigestDto> for DigestInformation {
+    fn from(value: &DigestDto) -> Self {
+        DigestInformation {
+            title: value.title.clone(),
+            body: value.body.clone(),
+            window: value.window,
+            max_count: value.max_count
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct CreateTemplateDto {
+    pub key: String,
+    pub locale: Option<String>,
+    pub title: String,
+    pub body: String,
+    pub params: Option<Vec<String>>,
+    pub optional_params: Option<Vec<String>>,
+    pub respect_quiet_hours: Option<bool>,
+    pub category: Option<String>,
+    pub digest: Option<DigestDto>,
+    pub options: Option<NotificationOptionsDto>,
+    pub platform_overrides: Option<HashMap<String, PlatformOverrideDto>>
+}
+
+impl CreateTemplateDto {
+    pub fn validate(&mut self) -> Result<&mut Self, Error> {
+        if self.key.trim().is_empty() {
+            return Err(Error::ValidationError("key cannot be empty".to_string()))
+        }
+        validate_content(&self.title, &self.body)?;
+
+        Ok(self)
+    }
+
+    pub fn get_notification_template(&self) -> NotificationTemplate {
+        get_notification_template(
+            &self.title,
+            &self.body,
+            &self.params,
+            &self.optional_params,
+            self.respect_quiet_hours,
+            &self.category,
+            &self.digest,
+            &self.options,
+            &self.platform_overrides
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct UpdateTemplateDto {
+    pub title: String,
+    pub body: String,
+    pub params: Option<Vec<String>>,
+    pub optional_params: Option<Vec<String>>,
+    pub respect_quiet_hours: Option<bool>,
+    pub category: Option<String>,
+    pub digest: Option<DigestDto>,
+    pub options: Option<NotificationOptionsDto>,
+    pub platform_overrides: Option<HashMap<String, PlatformOverrideDto>>
+}
+
+impl UpdateTemplateDto {
+    pub fn validate(&mut self) -> Result<&mut Self, Error> {
+        validate_content(&self.title, &self.body)?;
+
+        Ok(self)
+    }
+
+    pub fn get_notification_template(&self) -> NotificationTemplate {
+        get_notification_template(
+            &self.title,
+            &self.body,
+            &self.params,
+            &self.optional_params,
+            self.respect_quiet_hours,
+            &self.category,
+            &self.digest,
+            &self.options,
+            &self.platform_overrides
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct TemplateDto {
+    pub id: i32,
+    pub key: String,
+    pub locale: Option<String>,
+    pub title: String,
+    pub body: String,
+    pub params: Option<Vec<String>>,
+    pub optional_params: Option<Vec<String>>,
+    pub respect_quiet_hours: bool,
+    pub category: Option<String>,
+    pub digest: Option<DigestDto>,
+    pub options: NotificationOptionsDto,
+    pub platform_overrides: HashMap<String, PlatformOverrideDto>,
+    pub version: i32,
+    pub creation_date: String,
+    pub update_date: String
+}
+
+impl From<&NotificationTemplateFromRow> for TemplateDto {
+    fn from(value: &NotificationTemplateFromRow) -> Self {
+        TemplateDto {
+            id: value.id,
+            key: value.key.clone(),
+            locale: if value.is_default_locale() { None } else { Some(value.locale.clone()) },
+            title: value.title.clone(),
+            body: value.body.clone(),
+            params: value.params.as_ref().map(|params| params.0.clone()),
+            optional_params: value.optional_params.as_ref().map(|params| params.0.clone()),
+            respect_quiet_hours: value.respect_quiet_hours,
+            category: value.category.clone(),
+            digest: value.get_digest().as_ref().map(DigestDto::from),
+            options: NotificationOptionsDto::from(&value.get_options()),
+            platform_overrides: value.get_platform_overrides().into_iter()
+                .map(|(platform, information)| (platform, PlatformOverrideDto { title: information.title, body: information.body }))
+                .collect(),
+            version: value.version,
+            creation_date: value.creation_date.to_rfc3339(),
+            update_date: value.update_date.to_rfc3339()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct PaginatedTemplateDto {
+    pub total_count: i32,
+    pub total_page: i32,
+    pub next_page: Option<i32>,
+    pub previous_page: Option<i32>,
+    pub templates: Vec<TemplateDto>
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TemplateIdPathParameterDto {
+    pub template_id: String
+}
+
+impl TemplateIdPathParameterDto {
+    pub fn get_id_or_error(&mut self) -> Result<i32, Error> {
+        match self.template_id.parse::<i32>() {
+            Ok(id) => Ok(id),
+            Err(_) => Err(Error::ValidationError("template_id must be integer".to_string()))
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct PreviewTemplateDto {
+    pub device_type: String,
+    pub device_token: Option<String>,
+    pub locale: Option<String>,
+    #[schema(value_type = Option<Object>)]
+    pub extra_data: Option<ExtraData>,
+    pub template_data: Option<HashMap<String, String>>,
+    pub options: Option<NotificationOptionsDto>
+}
+
+impl PreviewTemplateDto {
+    const PREVIEW_DEVICE_TOKEN: &'static str = "preview-device-token";
+
+    pub fn validate(&mut self) -> Result<&mut Self, Error> {
+        if DeviceType::from_str(&self.device_type).is_err() {
+            return Err(Error::ValidationError("device_type must be Android or iOS".to_string()))
+        }
+        if let Some(locale) = &self.locale {
+            if !Locale(locale.clone()).is_valid() {
+                return Err(Error::ValidationError("locale must be a valid language tag (ex: fr, en-US)".to_string()))
+            }
+        }
+        self.get_notification_options().validate()?;
+        if let Some(violation) = self.extra_data.as_ref().and_then(|extra_data| extra_data.get_violation()) {
+            return Err(Error::ValidationError(violation))
+        }
+
+        Ok(self)
+    }
+
+    pub fn get_notification_options(&self) -> NotificationOptions {
+        self.options.as_ref().map(NotificationOptions::from).unwrap_or_default()
+    }
+
+    pub fn get_device_type(&self) -> DeviceType {
+        DeviceType::from(self.device_type.as_str())
+    }
+
+    pub fn get_device_token(&self) -> String {
+        self.device_token.clone().unwrap_or(Self::PREVIEW_DEVICE_TOKEN.to_string())
+    }
+
+    pub fn get_locale(&self) -> Option<Locale> {
+        self.locale.clone().map(Locale)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct TemplatePreviewDto {
+    pub title: String,
+    pub body: String,
+    pub version: Option<i32>,
+    pub locale: Option<String>,
+    pub payload_size: usize,
+    #[schema(value_type = Object)]
+    pub message: serde_json::Value
+}
+
+impl TemplatePreviewDto {
+    pub fn new(information: &NotificationInformation, payload_size: usize, message: serde_json::Value) -> Self {
+        TemplatePreviewDto {
+            title: information.title.clone(),
+            body: information.body.clone(),
+            version: information.version,
+            locale: information.locale.clone(),
+            payload_size,
+            message
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TemplateKeyPathParameterDto {
+    pub key: String
+}
+
+fn validate_content(title: &str, body: &str) -> Result<(), Error> {
+    if title.trim().is_empty() {
+        return Err(Error::ValidationError("title cannot be empty".to_string()))
+    }
+    if body.trim().is_empty() {
+        return Err(Error::ValidationError("body cannot be empty".to_string()))
+    }
+
+    Ok(())
+}
+
+fn get_notification_template(
+    title: &str,
+    body: &str,
+    params: &Option<Vec<String>>,
+    optional_params: &Option<Vec<String>>,
+    respect_quiet_hours: Option<bool>,
+    category: &Option<String>,
+    digest: &Option<DigestDto>,
+    options: &Option<NotificationOptionsDto>,
+    platform_overrides: &Option<HashMap<String, PlatformOverrideDto>>
+) -> NotificationTemplate {
+    NotificationTemplate {
+        title: title.to_string(),
+        body: body.to_string(),
+        params: params.clone(),
+        optional_params: optional_params.clone(),
+        respect_quiet_hours: respect_quiet_hours.unwrap_or(false),
+        category: category.as_ref().map(|category| category.trim().to_string()),
+        digest: digest.as_ref().map(DigestInformation::from),
+        options: options.as_ref().map(NotificationOptions::from).unwrap_or_default(),
+        platform_overrides: platform_overrides.clone().unwrap_or_default().into_iter()
+            .map(|(platform, information)| (platform, PlatformNotificationInformation { title: information.title, body: information.body }))
+            .collect()
+    }
+}
\ No newline at end of file
@@ -1,24 +1,254 @@
 use std::collections::HashMap;
-use std::convert::Infallible;
+use std::str::FromStr;
+use chrono::NaiveTime;
+use chrono_tz::Tz;
 use serde::{Serialize, Deserialize};
-use service::rows::user_from_row::UserFromRow;
+use service::{
+    audience::Audience,
+    campaign_service::NotificationCampaign,
+    notification_options::NotificationOptions,
+    notification_rate::NotificationRate,
+    rows::user_from_row::UserFromRow
+};
 use utoipa::ToSchema;
 use core::error::Error;
 use types::{
-    enums::NotificationType,
-    user::ListOfUsersWithExtraData
+    enums::NotificationChannel,
+    extra_data::ExtraData,
+    locale::Locale,
+    user::{DeviceSettings, ListOfUsersWithExtraData, QuietHours, UserAttributes, UserPreference}
+};
+use crate::dto::{
+    notification_options_dto::NotificationOptionsDto,
+    notification_rate_dto::NotificationRateDto
 };
 
 #[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
 pub struct UpdateUserTokenDto {
-    pub token: String
+    pub token: String,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub quiet_hours: Option<QuietHoursDto>,
+    pub tags: Option<Vec<String>>,
+    pub attributes: Option<HashMap<String, String>>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct QuietHoursDto {
+    pub start: String,
+    pub end: String
+}
+
+impl UpdateUserTokenDto {
+    pub fn validate(&mut self) -> Result<&mut Self, Error> {
+        if let Some(locale) = &self.locale {
+            if !Locale(locale.clone()).is_valid() {
+                return Err(Error::ValidationError("locale must be a valid language tag (ex: fr, en-US)".to_string()))
+            }
+        }
+
+        if let Some(timezone) = &self.timezone {
+            if Tz::from_str(timezone).is_err() {
+                return Err(Error::ValidationError("timezone must be a valid IANA timezone".to_string()))
+            }
+        }
+
+        if let Some(quiet_hours) = &self.quiet_hours {
+            if self.timezone.is_none() {
+                return Err(Error::ValidationError("quiet_hours requires a timezone".to_string()))
+            }
+
+            let quiet_hours = quiet_hours.get_quiet_hours()?;
+            if quiet_hours.start == quiet_hours.end {
+                return Err(Error::ValidationError("quiet_hours start and end must differ".to_string()))
+            }
+        }
+
+        Ok(self)
+    }
+
+    pub fn get_device_settings(&self) -> Result<DeviceSettings, Error> {
+        let quiet_hours = match &self.quiet_hours {
+            Some(quiet_hours) => Some(quiet_hours.get_quiet_hours()?),
+            None => None
+        };
+
+        Ok(DeviceSettings {
+            locale: self.locale.clone(),
+            timezone: self.timezone.clone(),
+            quiet_hours
+        })
+    }
+
+    // The device attributes are left untouched when neither tags nor attributes are given.
+    pub fn get_user_attributes(&self) -> Option<UserAttributes> {
+        if self.tags.is_none() && self.attributes.is_none() {
+            return None
+        }
+
+        Some(UserAttributes {
+            tags: self.tags.clone().unwrap_or_default(),
+            attributes: self.attributes.clone().unwrap_or_default()
+        })
+    }
+}
+
+impl QuietHoursDto {
+    pub fn get_quiet_hours(&self) -> Result<QuietHours, Error> {
+        let start = NaiveTime::parse_from_str(&self.start, "%H:%M");
+        let end = NaiveTime::parse_from_str(&self.end, "%H:%M");
+
+        match (start, end) {
+            (Ok(start), Ok(end)) => Ok(QuietHours { start, end }),
+            _ => Err(Error::ValidationError("quiet_hours must use the HH:MM format".to_string()))
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
 pub struct SendUserNotificationDto {
     pub notification_type: String,
-    pub extra_data: Option<HashMap<String, String>>,
-    pub template_data: Option<HashMap<String, String>>
+    #[schema(value_type = Option<Object>)]
+    pub extra_data: Option<ExtraData>,
+    pub template_data: Option<HashMap<String, String>>,
+    pub options: Option<NotificationOptionsDto>
+}
+
+impl SendUserNotificationDto {
+    pub fn validate(&mut self) -> Result<&mut Self, Error> {
+        if let Some(violation) = self.extra_data.as_ref().and_then(|extra_data| extra_data.get_violation()) {
+            return Err(Error::ValidationError(violation))
+        }
+        self.get_notification_options().validate()?;
+
+        Ok(self)
+    }
+
+    pub fn get_notification_options(&self) -> NotificationOptions {
+        self.options.as_ref().map(NotificationOptions::from).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct SendAudienceNotificationDto {
+    pub notification_type: String,
+    pub audience: String,
+    #[schema(value_type = Option<Object>)]
+    pub extra_data: Option<ExtraData>,
+    pub template_data: Option<HashMap<String, String>>,
+    pub options: Option<NotificationOptionsDto>,
+    pub rate: Option<NotificationRateDto>
+}
+
+impl SendAudienceNotificationDto {
+    pub fn validate(&mut self) -> Result<&mut Self, Error> {
+        self.get_audience()?;
+        if let Some(violation) = self.extra_data.as_ref().and_then(|extra_data| extra_data.get_violation()) {
+            return Err(Error::ValidationError(violation))
+        }
+        self.get_notification_options().validate()?;
+        if let Some(rate) = self.get_rate() {
+            rate.validate()?;
+        }
+
+        Ok(self)
+    }
+
+    pub fn get_rate(&self) -> Option<NotificationRate> {
+        self.rate.as_ref().map(NotificationRate::from)
+    }
+
+    pub fn get_audience(&self) -> Result<Audience, Error> {
+        Audience::parse(&self.audience)
+    }
+
+    pub fn get_notification_options(&self) -> NotificationOptions {
+        self.options.as_ref().map(NotificationOptions::from).unwrap_or_default()
+    }
+
+    pub fn get_notification_campaign(&self) -> NotificationCampaign {
+        NotificationCampaign {
+            name: format!("Audience notification {}", self.notification_type),
+            notification_type: self.notification_type.clone(),
+            audience: Some(self.audience.clone()),
+            extra_data: self.extra_data.clone(),
+            template_data: self.template_data.clone(),
+            options: self.get_notification_options(),
+            rate: self.get_rate(),
+            scheduled_date: None
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct UserAttributesDto {
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub attributes: HashMap<String, String>
+}
+
+impl From<UserAttributes> for UserAttributesDto {
+    fn from(value: UserAttributes) -> Self {
+        UserAttributesDto {
+            tags: value.tags,
+            attributes: value.attributes
+        }
+    }
+}
+
+impl From<&UserAttributesDto> for UserAttributes {
+    fn from(value: &UserAttributesDto) -> Self {
+        UserAttributes {
+            tags: value.tags.clone(),
+            attributes: value.attributes.clone()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct UserPreferenceDto {
+    pub category: String,
+    pub channel: String,
+    pub enabled: bool
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct UserPreferencesDto {
+    pub preferences: Vec<UserPreferenceDto>
+}
+
+impl UserPreferencesDto {
+    pub fn get_user_preferences(&self) -> Result<Vec<UserPreference>, Error> {
+        let mut preferences: Vec<UserPreference> = Vec::from([]);
+        for preference in &self.preferences {
+            let channel = match NotificationChannel::from_str(&preference.channel) {
+                Ok(channel) => channel,
+                Err(_) => return Err(Error::ValidationError(format!("Unknown channel: {}", preference.channel)))
+            };
+            preferences.push(UserPreference {
+                category: preference.category.trim().to_string(),
+                channel,
+                enabled: preference.enabled
+            });
+        }
+
+        Ok(preferences)
+    }
+}
+
+impl From<Vec<UserPreference>> for UserPreferencesDto {
+    fn from(value: Vec<UserPreference>) -> Self {
+        UserPreferencesDto {
+            preferences: value.into_iter()
+                .map(|preference| UserPreferenceDto {
+                    category: preference.category,
+                    channel: preference.channel.to_string(),
+                    enabled: preference.enabled
+                })
+                .collect()
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
@@ -50,14 +280,16 @@ impl From<&UserFromRow> for UserDto {
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct UsersWithExtraDataDto {
     pub id: i32,
-    pub extra_data: Option<HashMap<String, String>>,
+    #[schema(value_type = Option<Object>)]
+    pub extra_data: Option<ExtraData>,
     pub template_data: Option<HashMap<String, String>>
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct SendUsersNotificationDto {
     pub notification_type: String,
-    pub users: ListOfUsersWithExtraData
+    pub users: ListOfUsersWithExtraData,
+    pub rate: Option<NotificationRateDto>
 }
 
 impl SendUsersNotificationDto {
@@ -66,11 +298,23 @@ impl SendUsersNotificationDto {
             return Err(Error::ValidationError("ids cannot be empty".to_string()))
         }
 
-        let notification_type: Result<NotificationType, Infallible> = self.clone().notification_type.try_into();
-        match notification_type {
-            Ok(_) => Ok(self),
-            Err(_) => Err(Error::ValidationError("Bad notification type given".to_string()))
+        let violation = self.users.iter()
+            .find_map(|user| user.extra_data.as_ref()
+                .and_then(|extra_data| extra_data.get_violation())
+                .map(|violation| format!("user {}: {}", user.id, violation))
+            );
+        if let Some(violation) = violation {
+            return Err(Error::ValidationError(violation))
+        }
+        if let Some(rate) = self.get_rate() {
+            rate.validate()?;
         }
+
+        Ok(self)
+    }
+
+    pub fn get_rate(&self) -> Option<NotificationRate> {
+        self.rate.as_ref().map(NotificationRate::from)
     }
 }
 
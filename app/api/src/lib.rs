@@ -4,16 +4,24 @@ use core::config::Config;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 use migration::Migrator;
+use service::notification_catalog::NotificationCatalog;
 use crate::{
     controllers::{
         health_check::web_health_check,
-        users::web_users
+        users::web_users,
+        schedules::web_schedules,
+        templates::web_templates,
+        campaigns::web_campaigns,
+        api_keys::web_api_keys,
+        apps::web_apps
     },
+    middlewares::rate_limit::RateLimit,
     swagger::api_doc::ApiDoc
 };
 
 mod controllers;
 mod dto;
+mod middlewares;
 mod swagger;
 
 #[actix_web::main]
@@ -34,10 +42,25 @@ pub async fn main() -> std::io::Result<()> {
         }
     }
 
-    HttpServer::new(|| {
+    if let Err(err) = NotificationCatalog::init().and_then(|_| NotificationCatalog::watch()) {
+        panic!("Could not load notifications catalog : {err:?}")
+    }
+
+    let rate_limit = match RateLimit::from_config() {
+        Ok(rate_limit) => rate_limit,
+        Err(err) => panic!("Invalid API_RATE_LIMIT or API_KEY_RATE_LIMIT : {err:?}")
+    };
+
+    HttpServer::new(move || {
         App::new()
+            .wrap(rate_limit.clone())
             .service(web_health_check())
             .service(web_users())
+            .service(web_schedules())
+            .service(web_templates())
+            .service(web_campaigns())
+            .service(web_api_keys())
+            .service(web_apps())
             .service(
                 SwaggerUi::new("/swagger/{_:.*}")
                     .url("/swagger/doc.json", ApiDoc::openapi()),
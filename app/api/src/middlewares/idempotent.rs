@@ -0,0 +1,170 @@
+use std::{
+    future::{ready, Future, Ready},
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll}
+};
+use actix_http::h1;
+use actix_web::{
+    body::{self, EitherBody},
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::{header, StatusCode},
+    web, HttpMessage, HttpResponse
+};
+use core::{
+    api_key_context::ApiKeyContext,
+    config::Config,
+    error::Error
+};
+use service::{
+    redis_service::RedisService,
+    rows::idempotent_response_row::IdempotentResponseRow
+};
+
+// Replays the response of a request sent again with the same Idempotency-Key, so a retried send is only sent once.
+// It reads the app of the api key, so routes list it before RequireScope : the last wrap of a route is the outermost.
+pub struct Idempotent;
+
+impl Idempotent {
+    const IDEMPOTENCY_KEY_HEADER_NAME: &'static str = "Idempotency-Key";
+    const REPLAYED_HEADER_NAME: &'static str = "Idempotency-Replayed";
+    const MAX_KEY_LENGTH: usize = 255;
+    // A crashed request frees its key after this delay rather than the whole window.
+    const IN_PROGRESS_TTL: u64 = 300;
+
+    fn get_key(req: &ServiceRequest) -> Result<Option<String>, Error> {
+        let key = match req.headers().get(Self::IDEMPOTENCY_KEY_HEADER_NAME) {
+            Some(key) => key.to_str().map(|key| key.trim().to_string()).unwrap_or_default(),
+            None => return Ok(None)
+        };
+        if key.is_empty() || key.len() > Self::MAX_KEY_LENGTH {
+            return Err(Error::HeaderError(format!("Idempotency-Key header must be 1 to {} characters long", Self::MAX_KEY_LENGTH)))
+        }
+
+        let app_id = match req.extensions().get::<ApiKeyContext>() {
+            Some(api_key_context) => api_key_context.app_id,
+            None => return Err(Error::UnauthorizedError("Missing api key".to_string()))
+        };
+
+        Ok(Some(format!("{}:{}", app_id, key)))
+    }
+
+    // Returns the stored response of the key, or reserves it for this request.
+    async fn begin(key: &str, fingerprint: &str) -> Result<Option<HttpResponse>, Error> {
+        let row = IdempotentResponseRow::new(fingerprint.to_string());
+        let existing = match RedisService::new().reserve_idempotency_key(key, &row, Self::IN_PROGRESS_TTL).await? {
+            Some(existing) => existing,
+            None => return Ok(None)
+        };
+
+        if existing.fingerprint != fingerprint {
+            return Err(Error::ConflictError("Idempotency-Key was already used with a different request".to_string()))
+        }
+        if !existing.is_completed() {
+            return Err(Error::ConflictError("A request with this Idempotency-Key is in progress".to_string()))
+        }
+
+        let mut response = HttpResponse::build(StatusCode::from_u16(existing.status.unwrap()).unwrap_or(StatusCode::OK));
+        if let Some(content_type) = existing.content_type {
+            response.content_type(content_type);
+        }
+        response.insert_header((Self::REPLAYED_HEADER_NAME, "true"));
+
+        Ok(Some(response.body(existing.body.unwrap_or_default())))
+    }
+
+    // Server errors release the key, they are worth retrying.
+    async fn complete(key: &str, fingerprint: &str, response: &HttpResponse<web::Bytes>) -> Result<(), Error> {
+        let mut redis_service = RedisService::new();
+        if response.status().is_server_error() {
+            return redis_service.release_idempotency_key(key).await
+        }
+
+        let row = IdempotentResponseRow {
+            fingerprint: fingerprint.to_string(),
+            status: Some(response.status().as_u16()),
+            content_type: response.headers().get(header::CONTENT_TYPE)
+                .and_then(|content_type| content_type.to_str().ok())
+                .map(String::from),
+            body: Some(String::from_utf8_lossy(response.body()).to_string())
+        };
+
+        redis_service.store_idempotent_response(key, &row, Config::get_idempotency_key_ttl()).await
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Idempotent
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: body::MessageBody + 'static
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Transform = IdempotentMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(IdempotentMiddleware {
+            service: Rc::new(service)
+        }))
+    }
+}
+
+pub struct IdempotentMiddleware<S> {
+    service: Rc<S>
+}
+
+impl<S, B> Service<ServiceRequest> for IdempotentMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: body::MessageBody + 'static
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    // The local `core` crate shadows the one `forward_ready!` expands to.
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            let key = match Idempotent::get_key(&req) {
+                Ok(Some(key)) => key,
+                Ok(None) => return Ok(service.call(req).await?.map_into_left_body()),
+                Err(err) => return Ok(req.error_response(err).map_into_right_body())
+            };
+
+            // The body is read for the fingerprint, then given back to the handler.
+            let request_body = req.extract::<web::Bytes>().await?;
+            let (_, mut payload) = h1::Payload::create(true);
+            payload.unread_data(request_body.clone());
+            req.set_payload(payload.into());
+
+            let fingerprint = IdempotentResponseRow::get_fingerprint(req.method().as_str(), req.path(), &request_body);
+            match Idempotent::begin(&key, &fingerprint).await {
+                Ok(Some(response)) => return Ok(req.into_response(response).map_into_right_body()),
+                Ok(None) => {},
+                Err(err) => return Ok(req.error_response(err).map_into_right_body())
+            }
+
+            let (req, response) = service.call(req).await?.into_parts();
+            let (response, response_body) = response.into_parts();
+            let response_body = match body::to_bytes(response_body).await {
+                Ok(response_body) => response_body,
+                Err(err) => {
+                    RedisService::new().release_idempotency_key(&key).await?;
+                    return Err(actix_web::error::ErrorInternalServerError(err.into().to_string()))
+                }
+            };
+            let response = response.set_body(response_body);
+            Idempotent::complete(&key, &fingerprint, &response).await?;
+
+            Ok(ServiceResponse::new(req, response.map_into_boxed_body()).map_into_right_body())
+        })
+    }
+}
\ No newline at end of file
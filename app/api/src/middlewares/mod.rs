@@ -0,0 +1,3 @@
+pub mod require_scope;
+pub mod rate_limit;
+pub mod idempotent;
\ No newline at end of file
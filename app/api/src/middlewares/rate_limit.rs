@@ -0,0 +1,190 @@
+use std::{
+    future::{ready, Future, Ready},
+    net::IpAddr,
+    pin::Pin,
+    rc::Rc,
+    sync::Arc,
+    task::{Context, Poll}
+};
+use actix_web::{
+    body::EitherBody,
+    dev::{Service, ServiceRequest, ServiceResponse, Transform}
+};
+use core::{
+    api_key_context::ApiKeyContext,
+    config::Config,
+    error::Error
+};
+use service::{
+    notification_rate::NotificationRate,
+    redis_service::RedisService
+};
+
+// Limits the requests of every address with a token bucket kept in Redis, before the api key is even read.
+#[derive(Clone)]
+pub struct RateLimit(pub NotificationRate, pub Arc<Vec<IpAddr>>);
+
+impl RateLimit {
+    const FORWARDED_FOR_HEADER_NAME: &'static str = "X-Forwarded-For";
+    const UNLIMITED_PATHS: [&'static str; 1] = ["/health_check"];
+
+    pub fn from_config() -> Result<Self, Error> {
+        // Parsed per request afterwards, checked here so a typo stops the api at startup.
+        NotificationRate::parse(&Config::get_api_key_rate_limit())?;
+
+        Ok(RateLimit(NotificationRate::parse(&Config::get_api_rate_limit())?, Arc::new(Config::get_trusted_proxies())))
+    }
+
+    // Limits the authenticated api key, whatever address it is used from.
+    pub async fn check_api_key(api_key_context: &ApiKeyContext) -> Result<(), Error> {
+        let rate = NotificationRate::parse(&Config::get_api_key_rate_limit())?;
+        let client = match api_key_context.api_key_id {
+            Some(api_key_id) => format!("api_key:{}", api_key_id),
+            None => "api_key:admin".to_string()
+        };
+
+        Self::take_token(&client, &rate).await
+    }
+
+    // The forwarded addresses are only read from trusted proxies, the closest untrusted one being the client.
+    fn get_address(req: &ServiceRequest, trusted_proxies: &[IpAddr]) -> String {
+        let mut address = match req.peer_addr() {
+            Some(peer) => peer.ip(),
+            None => return "unknown".to_string()
+        };
+
+        let forwarded_for = req.headers().get_all(Self::FORWARDED_FOR_HEADER_NAME)
+            .filter_map(|header| header.to_str().ok())
+            .flat_map(|header| header.split(','))
+            .map(|hop| hop.trim().to_string())
+            .collect::<Vec<String>>();
+
+        for hop in forwarded_for.iter().rev() {
+            if !trusted_proxies.contains(&address) {
+                break
+            }
+
+            match hop.parse::<IpAddr>() {
+                Ok(hop) => address = hop,
+                Err(_) => break
+            }
+        }
+
+        address.to_string()
+    }
+
+    async fn check(req: &ServiceRequest, rate: &NotificationRate, trusted_proxies: &[IpAddr]) -> Result<(), Error> {
+        if Self::UNLIMITED_PATHS.iter().any(|path| req.path().starts_with(path)) {
+            return Ok(())
+        }
+
+        Self::take_token(&format!("address:{}", Self::get_address(req, trusted_proxies)), rate).await
+    }
+
+    async fn take_token(client: &str, rate: &NotificationRate) -> Result<(), Error> {
+        let wait = RedisService::new().take_api_token(client, rate).await?;
+        if wait > 0 {
+            return Err(Error::TooManyRequestsError(
+                format!("Rate limit of {} requests per {} exceeded", rate.messages, rate.per),
+                (wait + 999) / 1000
+            ))
+        }
+
+        Ok(())
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Transform = RateLimitMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitMiddleware {
+            service: Rc::new(service),
+            rate: self.0.clone(),
+            trusted_proxies: Arc::clone(&self.1)
+        }))
+    }
+}
+
+pub struct RateLimitMiddleware<S> {
+    service: Rc<S>,
+    rate: NotificationRate,
+    trusted_proxies: Arc<Vec<IpAddr>>
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    // The local `core` crate shadows the one `forward_ready!` expands to.
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let rate = self.rate.clone();
+        let trusted_proxies = Arc::clone(&self.trusted_proxies);
+
+        Box::pin(async move {
+            match RateLimit::check(&req, &rate, &trusted_proxies).await {
+                Ok(_) => {
+                    let response = service.call(req).await?;
+
+                    Ok(response.map_into_left_body())
+                },
+                Err(err) => Ok(req.error_response(err).map_into_right_body())
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    const PROXY: &str = "10.0.0.1";
+
+    fn get_address(peer: &str, forwarded_for: Option<&str>) -> String {
+        let mut req = TestRequest::default().peer_addr(format!("{}:443", peer).parse().unwrap());
+        if let Some(forwarded_for) = forwarded_for {
+            req = req.insert_header((RateLimit::FORWARDED_FOR_HEADER_NAME, forwarded_for));
+        }
+
+        RateLimit::get_address(&req.to_srv_request(), &[PROXY.parse().unwrap()])
+    }
+
+    #[test]
+    fn get_address_ignores_forwarded_for_from_untrusted_peers() {
+        assert_eq!(get_address("203.0.113.7", None), "203.0.113.7");
+        assert_eq!(get_address("203.0.113.7", Some("198.51.100.1")), "203.0.113.7");
+    }
+
+    #[test]
+    fn get_address_reads_the_closest_untrusted_hop_behind_trusted_proxies() {
+        assert_eq!(get_address(PROXY, Some("198.51.100.1")), "198.51.100.1");
+        // The leftmost hops are set by the client and cannot be trusted.
+        assert_eq!(get_address(PROXY, Some("192.0.2.9, 198.51.100.1")), "198.51.100.1");
+        assert_eq!(get_address(PROXY, Some("198.51.100.1, 10.0.0.1")), "198.51.100.1");
+    }
+
+    #[test]
+    fn get_address_falls_back_to_the_last_trusted_address() {
+        assert_eq!(get_address(PROXY, None), PROXY);
+        assert_eq!(get_address(PROXY, Some("not-an-address")), PROXY);
+    }
+}
\ No newline at end of file
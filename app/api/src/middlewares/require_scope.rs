@@ -0,0 +1,121 @@
+use std::{
+    future::{ready, Future, Ready},
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll}
+};
+use actix_web::{
+    body::EitherBody,
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    HttpMessage
+};
+use core::{
+    api_key_context::ApiKeyContext,
+    error::Error
+};
+use service::api_key_service::ApiKeyService;
+use crate::middlewares::rate_limit::RateLimit;
+use types::enums::ApiKeyScope;
+
+// Rejects requests whose api key is missing, unknown, revoked or lacks the scope.
+pub struct RequireScope(pub ApiKeyScope);
+
+impl RequireScope {
+    const AUTHORIZATION_HEADER_NAME: &'static str = "Authorization";
+    const API_KEY_HEADER_NAME: &'static str = "X-API-KEY";
+    const BEARER_PREFIX: &'static str = "Bearer ";
+    const APP_ID_HEADER_NAME: &'static str = "X-APP-ID";
+
+    fn get_api_key(req: &ServiceRequest) -> Option<String> {
+        if let Some(api_key) = req.headers().get(Self::API_KEY_HEADER_NAME) {
+            return api_key.to_str().ok().map(|api_key| api_key.trim().to_string())
+        }
+
+        req.headers().get(Self::AUTHORIZATION_HEADER_NAME)
+            .and_then(|authorization| authorization.to_str().ok())
+            .and_then(|authorization| authorization.strip_prefix(Self::BEARER_PREFIX))
+            .map(|api_key| api_key.trim().to_string())
+    }
+
+    fn get_app_id(req: &ServiceRequest) -> Result<Option<i32>, Error> {
+        match req.headers().get(Self::APP_ID_HEADER_NAME) {
+            Some(app_id) => match app_id.to_str().ok().and_then(|app_id| app_id.trim().parse::<i32>().ok()) {
+                Some(app_id) => Ok(Some(app_id)),
+                None => Err(Error::HeaderError("X-APP-ID header must be integer".to_string()))
+            },
+            None => Ok(None)
+        }
+    }
+
+    async fn authorize(req: &ServiceRequest, scope: ApiKeyScope) -> Result<ApiKeyContext, Error> {
+        let api_key = match Self::get_api_key(req) {
+            Some(api_key) if !api_key.is_empty() => api_key,
+            _ => return Err(Error::UnauthorizedError("Missing api key".to_string()))
+        };
+
+        let api_key_context = ApiKeyService::new().authenticate(&api_key, Self::get_app_id(req)?).await?;
+        if !api_key_context.has_scope(scope) {
+            return Err(Error::ForbiddenError(format!("Api key lacks the {} scope", scope)))
+        }
+
+        RateLimit::check_api_key(&api_key_context).await?;
+
+        Ok(api_key_context)
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequireScope
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Transform = RequireScopeMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequireScopeMiddleware {
+            service: Rc::new(service),
+            scope: self.0
+        }))
+    }
+}
+
+pub struct RequireScopeMiddleware<S> {
+    service: Rc<S>,
+    scope: ApiKeyScope
+}
+
+impl<S, B> Service<ServiceRequest> for RequireScopeMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    // The local `core` crate shadows the one `forward_ready!` expands to.
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let scope = self.scope;
+
+        Box::pin(async move {
+            match RequireScope::authorize(&req, scope).await {
+                Ok(api_key_context) => {
+                    req.extensions_mut().insert(api_key_context);
+                    let response = service.call(req).await?;
+
+                    Ok(response.map_into_left_body())
+                },
+                Err(err) => Ok(req.error_response(err).map_into_right_body())
+            }
+        })
+    }
+}
\ No newline at end of file
@@ -3,12 +3,56 @@ use crate::{
     dto::{
         user_dto::{
             UpdateUserTokenDto,
+            QuietHoursDto,
             UserDto,
             PaginatedUserDto,
             SendUserNotificationDto,
-            SendUsersNotificationDto
+            SendUsersNotificationDto,
+            SendAudienceNotificationDto,
+            UserAttributesDto,
+            UserPreferenceDto,
+            UserPreferencesDto
         },
-        pagination_dto::PaginationDto
+        pagination_dto::PaginationDto,
+        notification_options_dto::{
+            NotificationOptionsDto,
+            AndroidNotificationOptionsDto,
+            IosNotificationOptionsDto
+        },
+        notification_rate_dto::NotificationRateDto,
+        schedule_dto::{
+            CreateScheduleDto,
+            ScheduleDto,
+            PaginatedScheduleDto
+        },
+        template_dto::{
+            PlatformOverrideDto,
+            DigestDto,
+            CreateTemplateDto,
+            UpdateTemplateDto,
+            TemplateDto,
+            PaginatedTemplateDto,
+            PreviewTemplateDto,
+            TemplatePreviewDto
+        },
+        campaign_dto::{
+            CreateCampaignDto,
+            CampaignDto,
+            CampaignProgressDto,
+            PaginatedCampaignDto
+        },
+        api_key_dto::{
+            CreateApiKeyDto,
+            ApiKeyDto,
+            CreatedApiKeyDto,
+            PaginatedApiKeyDto
+        },
+        app_dto::{
+            CreateAppDto,
+            UpdateAppDto,
+            AppDto,
+            PaginatedAppDto
+        }
     }
 };
 use core::responses::ErrorResponse;
@@ -20,18 +64,80 @@ use core::responses::ErrorResponse;
         super::super::controllers::users::update_user_token,
         super::super::controllers::users::send_user_notification,
         super::super::controllers::users::send_users_notifications,
+        super::super::controllers::users::send_audience_notification,
+        super::super::controllers::users::fetch_user_attributes,
+        super::super::controllers::users::update_user_attributes,
+        super::super::controllers::users::fetch_user_preferences,
+        super::super::controllers::users::update_user_preferences,
+        super::super::controllers::schedules::create_schedule,
+        super::super::controllers::schedules::fetch_schedule_list_paginated,
+        super::super::controllers::schedules::pause_schedule,
+        super::super::controllers::schedules::resume_schedule,
+        super::super::controllers::schedules::delete_schedule,
+        super::super::controllers::templates::create_template,
+        super::super::controllers::templates::fetch_template_list_paginated,
+        super::super::controllers::templates::fetch_template,
+        super::super::controllers::templates::update_template,
+        super::super::controllers::templates::delete_template,
+        super::super::controllers::templates::preview_template,
+        super::super::controllers::campaigns::create_campaign,
+        super::super::controllers::campaigns::fetch_campaign_list_paginated,
+        super::super::controllers::campaigns::fetch_campaign,
+        super::super::controllers::campaigns::start_campaign,
+        super::super::controllers::campaigns::pause_campaign,
+        super::super::controllers::campaigns::resume_campaign,
+        super::super::controllers::campaigns::cancel_campaign,
+        super::super::controllers::api_keys::create_api_key,
+        super::super::controllers::api_keys::fetch_api_key_list_paginated,
+        super::super::controllers::api_keys::revoke_api_key,
+        super::super::controllers::apps::create_app,
+        super::super::controllers::apps::fetch_app_list_paginated,
+        super::super::controllers::apps::fetch_app,
+        super::super::controllers::apps::update_app,
     ),
     components(
         schemas(
             UpdateUserTokenDto,
+            QuietHoursDto,
             UserDto,
             SendUserNotificationDto,
             PaginatedUserDto,
             ErrorResponse,
             PaginationDto,
-            SendUsersNotificationDto
+            NotificationOptionsDto,
+            AndroidNotificationOptionsDto,
+            IosNotificationOptionsDto,
+            NotificationRateDto,
+            SendUsersNotificationDto,
+            SendAudienceNotificationDto,
+            UserAttributesDto,
+            UserPreferenceDto,
+            UserPreferencesDto,
+            CreateScheduleDto,
+            ScheduleDto,
+            PaginatedScheduleDto,
+            PlatformOverrideDto,
+            DigestDto,
+            CreateTemplateDto,
+            UpdateTemplateDto,
+            TemplateDto,
+            PaginatedTemplateDto,
+            PreviewTemplateDto,
+            TemplatePreviewDto,
+            CreateCampaignDto,
+            CampaignDto,
+            CampaignProgressDto,
+            PaginatedCampaignDto,
+            CreateApiKeyDto,
+            ApiKeyDto,
+            CreatedApiKeyDto,
+            PaginatedApiKeyDto,
+            CreateAppDto,
+            UpdateAppDto,
+            AppDto,
+            PaginatedAppDto
         )
     ),
-    tags((name = "Health-Check"), (name = "Users")),
+    tags((name = "Health-Check"), (name = "Users"), (name = "Schedules"), (name = "Templates"), (name = "Campaigns"), (name = "Api-Keys"), (name = "Apps")),
 )]
 pub struct ApiDoc;
\ No newline at end of file
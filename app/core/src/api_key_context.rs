@@ -0,0 +1,26 @@
+use types::enums::ApiKeyScope;
+use crate::error::Error;
+
+#[derive(Clone, Debug)]
+pub struct ApiKeyContext {
+    pub api_key_id: Option<i32>,
+    pub app_id: i32,
+    pub name: String,
+    pub scopes: Vec<ApiKeyScope>,
+}
+
+impl ApiKeyContext {
+    // The admin scope grants every other scope.
+    pub fn has_scope(&self, scope: ApiKeyScope) -> bool {
+        self.scopes.iter().any(|granted| *granted == scope || *granted == ApiKeyScope::Admin)
+    }
+
+    // Apps are managed with the ADMIN_API_KEY setting only, stored keys belong to a single app.
+    pub fn check_bootstrap_key(&self) -> Result<(), Error> {
+        if self.api_key_id.is_some() {
+            return Err(Error::ForbiddenError("Only the ADMIN_API_KEY can manage apps".to_string()))
+        }
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -1,4 +1,4 @@
-use std::env;
+use std::{env, net::IpAddr, path::Path};
 use sqlx::{Pool, MySql, MySqlPool, Executor};
 use crate::error::Error;
 
@@ -10,6 +10,21 @@ impl Config {
     const APP_MODE_ENV_NAME: &'static str = "APP_MODE";
     const REDIS_HOST_ENV_NAME: &'static str = "REDIS_HOST";
     const GOOGLE_SERVICE_ACCOUNT_CREDENTIALS_PATH_ENV_NAME: &'static str = "GOOGLE_SERVICE_ACCOUNT_CREDENTIALS_PATH";
+    const NOTIFICATION_RESOURCES_PATH_ENV_NAME: &'static str = "NOTIFICATION_RESOURCES_PATH";
+    const ADMIN_API_KEY_ENV_NAME: &'static str = "ADMIN_API_KEY";
+    const DEVICE_REGISTRATION_SECRET_ENV_NAME: &'static str = "DEVICE_REGISTRATION_SECRET";
+    const API_RATE_LIMIT_ENV_NAME: &'static str = "API_RATE_LIMIT";
+    const API_RATE_LIMIT_DEFAULT: &'static str = "100/second";
+    const API_KEY_RATE_LIMIT_ENV_NAME: &'static str = "API_KEY_RATE_LIMIT";
+    const API_KEY_RATE_LIMIT_DEFAULT: &'static str = "1000/second";
+    const TRUSTED_PROXIES_ENV_NAME: &'static str = "TRUSTED_PROXIES";
+    const USER_HOURLY_NOTIFICATION_LIMIT_ENV_NAME: &'static str = "USER_HOURLY_NOTIFICATION_LIMIT";
+    const IDEMPOTENCY_KEY_TTL_ENV_NAME: &'static str = "IDEMPOTENCY_KEY_TTL";
+    const IDEMPOTENCY_KEY_TTL_DEFAULT: u64 = 86400;
+    const NOTIFICATION_RESOURCES_DEFAULT_PATHS: [&'static str; 2] = [
+        "./resources/notifications.yml",
+        "../resources/notifications.yml"
+    ];
 
     pub async fn check_config() -> Result<(), Error> {
         env::var(Config::DATABASE_URL_ENV_NAME).expect("DATABASE_URL must be set");
@@ -59,7 +74,69 @@ impl Config {
         env::var(Config::GOOGLE_SERVICE_ACCOUNT_CREDENTIALS_PATH_ENV_NAME).unwrap()
     }
 
-    pub fn get_notification_resources_path() -> String {
-        "../resources/notifications.yml".to_string()
+    // Optional, lets an operator create the first api keys.
+    pub fn get_admin_api_key() -> Option<String> {
+        env::var(Config::ADMIN_API_KEY_ENV_NAME).ok().filter(|key| !key.is_empty())
+    }
+
+    // Optional, devices cannot register while neither this setting nor the app holds a secret.
+    pub fn get_device_registration_secret() -> Option<String> {
+        env::var(Config::DEVICE_REGISTRATION_SECRET_ENV_NAME).ok().filter(|secret| !secret.is_empty())
+    }
+
+    // Requests allowed per address, as `<requests>/<second|minute>`.
+    pub fn get_api_rate_limit() -> String {
+        env::var(Config::API_RATE_LIMIT_ENV_NAME).ok()
+            .filter(|rate_limit| !rate_limit.is_empty())
+            .unwrap_or_else(|| Config::API_RATE_LIMIT_DEFAULT.to_string())
+    }
+
+    // Requests allowed per api key, shared by every address using it.
+    pub fn get_api_key_rate_limit() -> String {
+        env::var(Config::API_KEY_RATE_LIMIT_ENV_NAME).ok()
+            .filter(|rate_limit| !rate_limit.is_empty())
+            .unwrap_or_else(|| Config::API_KEY_RATE_LIMIT_DEFAULT.to_string())
+    }
+
+    // Optional, comma separated addresses whose X-Forwarded-For header is trusted.
+    pub fn get_trusted_proxies() -> Vec<IpAddr> {
+        env::var(Config::TRUSTED_PROXIES_ENV_NAME).unwrap_or_default()
+            .split(',')
+            .map(|proxy| proxy.trim())
+            .filter(|proxy| !proxy.is_empty())
+            .map(|proxy| proxy.parse::<IpAddr>().expect("TRUSTED_PROXIES must be comma separated ip addresses"))
+            .collect()
+    }
+
+    // Optional, devices receive any number of notifications when it is not set.
+    pub fn get_user_hourly_notification_limit() -> Option<i64> {
+        env::var(Config::USER_HOURLY_NOTIFICATION_LIMIT_ENV_NAME).ok()
+            .filter(|limit| !limit.is_empty())
+            .map(|limit| limit.trim().parse::<i64>().expect("USER_HOURLY_NOTIFICATION_LIMIT must be integer"))
+            .filter(|limit| *limit > 0)
+    }
+
+    // Seconds during which a replayed Idempotency-Key gets the original response.
+    pub fn get_idempotency_key_ttl() -> u64 {
+        env::var(Config::IDEMPOTENCY_KEY_TTL_ENV_NAME).ok()
+            .filter(|ttl| !ttl.is_empty())
+            .map(|ttl| ttl.trim().parse::<u64>().expect("IDEMPOTENCY_KEY_TTL must be integer"))
+            .unwrap_or(Config::IDEMPOTENCY_KEY_TTL_DEFAULT)
+    }
+
+    pub fn get_notification_resources_path() -> Result<String, Error> {
+        if let Some(path) = env::var(Config::NOTIFICATION_RESOURCES_PATH_ENV_NAME).ok().filter(|path| !path.is_empty()) {
+            return Ok(path);
+        }
+
+        // The api runs from the project root while the consumer runs from the command folder.
+        match Config::NOTIFICATION_RESOURCES_DEFAULT_PATHS.iter().find(|path| Path::new(path).exists()) {
+            Some(path) => Ok(path.to_string()),
+            None => Err(Error::ConfigError(format!(
+                "No notifications file found in {}, set {} to its path",
+                Config::NOTIFICATION_RESOURCES_DEFAULT_PATHS.join(" or "),
+                Config::NOTIFICATION_RESOURCES_PATH_ENV_NAME
+            )))
+        }
     }
 }
\ No newline at end of file
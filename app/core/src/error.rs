@@ -15,6 +15,12 @@ pub enum Error {
     NotFoundError(String),
     MissingDataError(String),
     ValidationError(String),
+    PayloadTooLargeError(String),
+    UnauthorizedError(String),
+    ForbiddenError(String),
+    ConflictError(String),
+    // The seconds to wait before retrying, sent in the Retry-After header.
+    TooManyRequestsError(String, i64),
 }
 
 impl From<Error> for String {
@@ -25,7 +31,12 @@ impl From<Error> for String {
             Error::HeaderError(text) => text.to_owned(),
             Error::NotFoundError(text) => text.to_owned(),
             Error::MissingDataError(text) => text.to_owned(),
-            Error::ValidationError(text) => text.to_owned()
+            Error::ValidationError(text) => text.to_owned(),
+            Error::PayloadTooLargeError(text) => text.to_owned(),
+            Error::UnauthorizedError(text) => text.to_owned(),
+            Error::ForbiddenError(text) => text.to_owned(),
+            Error::ConflictError(text) => text.to_owned(),
+            Error::TooManyRequestsError(text, _) => text.to_owned()
         }
     }
 }
@@ -54,6 +65,13 @@ impl Responder for Error {
     fn respond_to(self, _: &HttpRequest) -> HttpResponse<BoxBody> {
         match self {
             Error::ValidationError(text) => HttpResponse::BadRequest().json(ErrorResponse { message: text }),
+            Error::PayloadTooLargeError(text) => HttpResponse::BadRequest().json(ErrorResponse { message: text }),
+            Error::UnauthorizedError(text) => HttpResponse::Unauthorized().json(ErrorResponse { message: text }),
+            Error::ForbiddenError(text) => HttpResponse::Forbidden().json(ErrorResponse { message: text }),
+            Error::ConflictError(text) => HttpResponse::Conflict().json(ErrorResponse { message: text }),
+            Error::TooManyRequestsError(text, retry_after) => HttpResponse::TooManyRequests()
+                .insert_header(("Retry-After", retry_after.to_string()))
+                .json(ErrorResponse { message: text }),
             Error::NotFoundError(text) => HttpResponse::NotFound().json(
                 ErrorResponse { message: text }
             ),
@@ -72,6 +90,13 @@ impl From<Error> for HttpResponse {
     fn from(value: Error) -> Self {
         match value {
             Error::ValidationError(text) => HttpResponse::BadRequest().json(ErrorResponse { message: text }),
+            Error::PayloadTooLargeError(text) => HttpResponse::BadRequest().json(ErrorResponse { message: text }),
+            Error::UnauthorizedError(text) => HttpResponse::Unauthorized().json(ErrorResponse { message: text }),
+            Error::ForbiddenError(text) => HttpResponse::Forbidden().json(ErrorResponse { message: text }),
+            Error::ConflictError(text) => HttpResponse::Conflict().json(ErrorResponse { message: text }),
+            Error::TooManyRequestsError(text, retry_after) => HttpResponse::TooManyRequests()
+                .insert_header(("Retry-After", retry_after.to_string()))
+                .json(ErrorResponse { message: text }),
             Error::NotFoundError(text) => HttpResponse::NotFound().json(
                 ErrorResponse { message: text }
             ),
@@ -96,6 +121,11 @@ impl ResponseError for Error {
     fn status_code(&self) -> StatusCode {
         match self {
             Error::ValidationError(_) => StatusCode::BAD_REQUEST,
+            Error::PayloadTooLargeError(_) => StatusCode::BAD_REQUEST,
+            Error::UnauthorizedError(_) => StatusCode::UNAUTHORIZED,
+            Error::ForbiddenError(_) => StatusCode::FORBIDDEN,
+            Error::ConflictError(_) => StatusCode::CONFLICT,
+            Error::TooManyRequestsError(_, _) => StatusCode::TOO_MANY_REQUESTS,
             Error::HeaderError(_) => StatusCode::BAD_REQUEST,
             Error::NotFoundError(_) => StatusCode::NOT_FOUND,
             _ => StatusCode::INTERNAL_SERVER_ERROR
@@ -105,6 +135,13 @@ impl ResponseError for Error {
     fn error_response(&self) -> HttpResponse<BoxBody> {
         match self {
             Error::ValidationError(text) => HttpResponse::BadRequest().json(ErrorResponse { message: text.to_string() }),
+            Error::PayloadTooLargeError(text) => HttpResponse::BadRequest().json(ErrorResponse { message: text.to_string() }),
+            Error::UnauthorizedError(text) => HttpResponse::Unauthorized().json(ErrorResponse { message: text.to_string() }),
+            Error::ForbiddenError(text) => HttpResponse::Forbidden().json(ErrorResponse { message: text.to_string() }),
+            Error::ConflictError(text) => HttpResponse::Conflict().json(ErrorResponse { message: text.to_string() }),
+            Error::TooManyRequestsError(text, retry_after) => HttpResponse::TooManyRequests()
+                .insert_header(("Retry-After", retry_after.to_string()))
+                .json(ErrorResponse { message: text.to_string() }),
             Error::NotFoundError(text) => HttpResponse::NotFound().json(
                 ErrorResponse { message: text.to_string() }
             ),
@@ -1,6 +1,7 @@
 use std::str::FromStr;
-use actix_web::HttpRequest;
+use actix_web::{HttpMessage, HttpRequest};
 use types::{device_id::DeviceId, enums::DeviceType};
+use crate::api_key_context::ApiKeyContext;
 use crate::error::Error;
 use crate::user_context::UserContext;
 
@@ -9,11 +10,22 @@ struct UserContextHeader {}
 impl UserContextHeader {
     const DEVICE_ID_HEADER_NAME: &'static str = "X-DEVICE-ID";
     const DEVICE_TYPE_HEADER_NAME: &'static str = "X-DEVICE-TYPE";
+    const DEVICE_SIGNATURE_HEADER_NAME: &'static str = "X-DEVICE-SIGNATURE";
 }
 
 pub fn get_user_context(req: HttpRequest) -> Result<UserContext, Error> {
+    // The device belongs to the app of the api key, set by the RequireScope middleware.
+    let app_id = match req.extensions().get::<ApiKeyContext>() {
+        Some(api_key_context) => api_key_context.app_id,
+        None => return Err(Error::UnauthorizedError("Missing api key".to_string()))
+    };
+
     let device_id_header = req.headers().get(UserContextHeader::DEVICE_ID_HEADER_NAME);
     let device_type_header = req.headers().get(UserContextHeader::DEVICE_TYPE_HEADER_NAME);
+    // Checked by the services registering the device, a missing signature is not a malformed request.
+    let device_signature = req.headers().get(UserContextHeader::DEVICE_SIGNATURE_HEADER_NAME)
+        .and_then(|header| header.to_str().ok())
+        .map(String::from);
 
     if device_id_header.is_none() {
         return Err(Error::HeaderError("Missing X-DEVICE-ID header".to_string()))
@@ -29,8 +41,10 @@ pub fn get_user_context(req: HttpRequest) -> Result<UserContext, Error> {
             Err(Error::HeaderError("Bad Device Type given".to_string()))
         }
         Ok(device_type) => Ok(UserContext {
+            app_id,
             device_id: DeviceId(String::from(device_id_header.unwrap().to_str().unwrap())),
-            device_type
+            device_type,
+            device_signature
         })
     }
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 pub mod error;
 pub mod config;
 pub mod user_context;
+pub mod api_key_context;
 pub mod http_helper;
 pub mod responses;
\ No newline at end of file
@@ -2,6 +2,8 @@ use types::{device_id::DeviceId, enums::DeviceType};
 
 #[derive(Clone, Debug)]
 pub struct UserContext {
+    pub app_id: i32,
     pub device_id: DeviceId,
     pub device_type: DeviceType,
+    pub device_signature: Option<String>,
 }
\ No newline at end of file
@@ -12,7 +12,23 @@ use crate::{
     },
     migrations::{
         migration_base00000::Migration as BaseMigration,
-        migration_init000000::Migration as FirstMigration
+        migration_init000000::Migration as FirstMigration,
+        migration_quiet_hours000001::Migration as QuietHoursMigration,
+        migration_schedules000002::Migration as SchedulesMigration,
+        migration_locale000003::Migration as LocaleMigration,
+        migration_templates000004::Migration as TemplatesMigration,
+        migration_template_version000005::Migration as TemplateVersionMigration,
+        migration_template_options000006::Migration as TemplateOptionsMigration,
+        migration_history_reason000007::Migration as HistoryReasonMigration,
+        migration_user_attributes000008::Migration as UserAttributesMigration,
+        migration_campaigns000009::Migration as CampaignsMigration,
+        migration_campaign_rate000010::Migration as CampaignRateMigration,
+        migration_api_keys000011::Migration as ApiKeysMigration,
+        migration_apps000012::Migration as AppsMigration,
+        migration_device_registration000013::Migration as DeviceRegistrationMigration,
+        migration_preferences000014::Migration as PreferencesMigration,
+        migration_digests000015::Migration as DigestsMigration,
+        migration_schedule_audience000016::Migration as ScheduleAudienceMigration
     }
 };
 
@@ -22,24 +38,35 @@ impl Migrator {
     pub async fn migrate() -> Result<(), Error> {
         Config::ping_database().await?;
 
-        let migrations = Vec::from([
-            FirstMigration {}
-        ]);
-        Self::execute_migrations(migrations).await?;
+        Self::create_migration_table_if_needed().await?;
+
+        Self::execute_migration(FirstMigration {}).await?;
+        Self::execute_migration(QuietHoursMigration {}).await?;
+        Self::execute_migration(SchedulesMigration {}).await?;
+        Self::execute_migration(LocaleMigration {}).await?;
+        Self::execute_migration(TemplatesMigration {}).await?;
+        Self::execute_migration(TemplateVersionMigration {}).await?;
+        Self::execute_migration(TemplateOptionsMigration {}).await?;
+        Self::execute_migration(HistoryReasonMigration {}).await?;
+        Self::execute_migration(UserAttributesMigration {}).await?;
+        Self::execute_migration(CampaignsMigration {}).await?;
+        Self::execute_migration(CampaignRateMigration {}).await?;
+        Self::execute_migration(ApiKeysMigration {}).await?;
+        Self::execute_migration(AppsMigration {}).await?;
+        Self::execute_migration(DeviceRegistrationMigration {}).await?;
+        Self::execute_migration(PreferencesMigration {}).await?;
+        Self::execute_migration(DigestsMigration {}).await?;
+        Self::execute_migration(ScheduleAudienceMigration {}).await?;
 
         Ok(())
     }
 }
 
 impl Migrator {
-    async fn execute_migrations(migrations: Vec<impl MigrationTrait>) -> Result<(), Error> {
-        Self::create_migration_table_if_needed().await?;
-
-        for mut migration in migrations {
-            if !Self::already_executed(migration.get_name()).await? {
-                migration.up().await?;
-                Self::add_migration(migration.get_name()).await?
-            }
+    async fn execute_migration(mut migration: impl MigrationTrait) -> Result<(), Error> {
+        if !Self::already_executed(migration.get_name()).await? {
+            migration.up().await?;
+            Self::add_migration(migration.get_name()).await?
         }
         Ok(())
     }
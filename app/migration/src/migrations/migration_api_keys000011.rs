@@ -0,0 +1,41 @@
+use crate::migration::MigrationTrait;
+use core::error::Error;
+
+pub struct Migration {}
+
+impl MigrationTrait for Migration {
+    async fn up(&mut self) -> Result<(), Error> {
+        let conn = Self::get_connection().await;
+        // Only the sha256 of a key is stored, the prefix helps recognizing it in listings.
+        sqlx::query(
+            r#"CREATE TABLE IF NOT EXISTS `api_keys` (
+                `id` INTEGER NOT NULL auto_increment primary key,
+                `name` VARCHAR(255) NOT NULL,
+                `key_prefix` VARCHAR(16) NOT NULL,
+                `key_hash` CHAR(64) NOT NULL,
+                `scopes` JSON NOT NULL,
+                `last_used_date` DATETIME NULL,
+                `revocation_date` DATETIME NULL,
+                `creation_date` DATETIME NOT NULL,
+                UNIQUE INDEX UNIQ_key_hash_api_keys (key_hash)
+                )"#
+        )
+            .execute(&conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&mut self) -> Result<(), Error> {
+        // TODO sql
+        Ok(())
+    }
+
+    fn get_name(&mut self) -> String {
+        "Migration_api_keys000011".to_string()
+    }
+
+    fn new() -> impl MigrationTrait {
+        Self {}
+    }
+}
@@ -0,0 +1,100 @@
+use crate::migration::MigrationTrait;
+use core::error::Error;
+
+pub struct Migration {}
+
+impl MigrationTrait for Migration {
+    async fn up(&mut self) -> Result<(), Error> {
+        let conn = Self::get_connection().await;
+        // Null paths fall back to the GOOGLE_SERVICE_ACCOUNT_CREDENTIALS_PATH and NOTIFICATION_RESOURCES_PATH settings.
+        sqlx::query(
+            r#"CREATE TABLE IF NOT EXISTS `apps` (
+                `id` INTEGER NOT NULL auto_increment primary key,
+                `name` VARCHAR(255) NOT NULL,
+                `google_service_account_credentials_path` VARCHAR(1024) NULL,
+                `notification_resources_path` VARCHAR(1024) NULL,
+                `creation_date` DATETIME NOT NULL,
+                UNIQUE INDEX UNIQ_name_apps (name)
+                )"#
+        )
+            .execute(&conn)
+            .await?;
+
+        // Existing devices, templates and keys belong to the default app.
+        sqlx::query(
+            "INSERT IGNORE INTO `apps` (id, name, creation_date) VALUES (1, 'default', NOW())"
+        )
+            .execute(&conn)
+            .await?;
+
+        sqlx::query(
+            r#"ALTER TABLE `users`
+                ADD COLUMN `app_id` INTEGER NOT NULL DEFAULT 1 AFTER `id`,
+                ADD INDEX IDX_app_id_device_users (app_id, device_id, device_type)
+                "#
+        )
+            .execute(&conn)
+            .await?;
+
+        sqlx::query(
+            r#"ALTER TABLE `history_notifications`
+                ADD COLUMN `app_id` INTEGER NOT NULL DEFAULT 1 AFTER `id`,
+                ADD INDEX IDX_app_id_history_notifications (app_id)
+                "#
+        )
+            .execute(&conn)
+            .await?;
+
+        sqlx::query(
+            r#"ALTER TABLE `notification_templates`
+                ADD COLUMN `app_id` INTEGER NOT NULL DEFAULT 1 AFTER `id`,
+                DROP INDEX UNIQ_key_locale_notification_templates,
+                ADD UNIQUE INDEX UNIQ_app_id_key_locale_notification_templates (app_id, `key`, `locale`)
+                "#
+        )
+            .execute(&conn)
+            .await?;
+
+        sqlx::query(
+            r#"ALTER TABLE `notification_schedules`
+                ADD COLUMN `app_id` INTEGER NOT NULL DEFAULT 1 AFTER `id`,
+                ADD INDEX IDX_app_id_notification_schedules (app_id)
+                "#
+        )
+            .execute(&conn)
+            .await?;
+
+        sqlx::query(
+            r#"ALTER TABLE `campaigns`
+                ADD COLUMN `app_id` INTEGER NOT NULL DEFAULT 1 AFTER `id`,
+                ADD INDEX IDX_app_id_campaigns (app_id)
+                "#
+        )
+            .execute(&conn)
+            .await?;
+
+        sqlx::query(
+            r#"ALTER TABLE `api_keys`
+                ADD COLUMN `app_id` INTEGER NOT NULL DEFAULT 1 AFTER `id`,
+                ADD INDEX IDX_app_id_api_keys (app_id)
+                "#
+        )
+            .execute(&conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&mut self) -> Result<(), Error> {
+        // TODO sql
+        Ok(())
+    }
+
+    fn get_name(&mut self) -> String {
+        "Migration_apps000012".to_string()
+    }
+
+    fn new() -> impl MigrationTrait {
+        Self {}
+    }
+}
@@ -0,0 +1,32 @@
+use crate::migration::MigrationTrait;
+use core::error::Error;
+
+pub struct Migration {}
+
+impl MigrationTrait for Migration {
+    async fn up(&mut self) -> Result<(), Error> {
+        let conn = Self::get_connection().await;
+        sqlx::query(
+            r#"ALTER TABLE `campaigns`
+                ADD COLUMN `rate` JSON NULL
+                "#
+        )
+            .execute(&conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&mut self) -> Result<(), Error> {
+        // TODO sql
+        Ok(())
+    }
+
+    fn get_name(&mut self) -> String {
+        "Migration_campaign_rate000010".to_string()
+    }
+
+    fn new() -> impl MigrationTrait {
+        Self {}
+    }
+}
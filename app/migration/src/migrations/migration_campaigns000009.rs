@@ -0,0 +1,55 @@
+use crate::migration::MigrationTrait;
+use core::error::Error;
+
+pub struct Migration {}
+
+impl MigrationTrait for Migration {
+    async fn up(&mut self) -> Result<(), Error> {
+        let conn = Self::get_connection().await;
+        sqlx::query(
+            r#"CREATE TABLE IF NOT EXISTS `campaigns` (
+                `id` INTEGER NOT NULL auto_increment primary key,
+                `name` VARCHAR(255) NOT NULL,
+                `notification_type` VARCHAR(255) NOT NULL,
+                `audience` VARCHAR(1024) NULL,
+                `extra_data` JSON NULL,
+                `template_data` JSON NULL,
+                `options` JSON NULL,
+                `status` VARCHAR(255) NOT NULL,
+                `scheduled_date` DATETIME NULL,
+                `last_user_id` INTEGER NOT NULL DEFAULT 0,
+                `queued_count` INTEGER NOT NULL DEFAULT 0,
+                `start_date` DATETIME NULL,
+                `completion_date` DATETIME NULL,
+                `creation_date` DATETIME NOT NULL,
+                INDEX IDX_status_campaigns (status, scheduled_date)
+                )"#
+        )
+            .execute(&conn)
+            .await?;
+
+        sqlx::query(
+            r#"ALTER TABLE `history_notifications`
+                ADD COLUMN `campaign_id` INTEGER NULL,
+                ADD INDEX IDX_campaign_id_history_notifications (campaign_id, status)
+                "#
+        )
+            .execute(&conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&mut self) -> Result<(), Error> {
+        // TODO sql
+        Ok(())
+    }
+
+    fn get_name(&mut self) -> String {
+        "Migration_campaigns000009".to_string()
+    }
+
+    fn new() -> impl MigrationTrait {
+        Self {}
+    }
+}
@@ -0,0 +1,32 @@
+use crate::migration::MigrationTrait;
+use core::error::Error;
+
+pub struct Migration {}
+
+impl MigrationTrait for Migration {
+    async fn up(&mut self) -> Result<(), Error> {
+        let conn = Self::get_connection().await;
+        sqlx::query(
+            r#"ALTER TABLE `apps`
+                ADD COLUMN `device_registration_secret` VARCHAR(255) NULL
+                "#
+        )
+            .execute(&conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&mut self) -> Result<(), Error> {
+        // TODO sql
+        Ok(())
+    }
+
+    fn get_name(&mut self) -> String {
+        "Migration_device_registration000013".to_string()
+    }
+
+    fn new() -> impl MigrationTrait {
+        Self {}
+    }
+}
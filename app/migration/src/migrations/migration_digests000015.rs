@@ -0,0 +1,32 @@
+use crate::migration::MigrationTrait;
+use core::error::Error;
+
+pub struct Migration {}
+
+impl MigrationTrait for Migration {
+    async fn up(&mut self) -> Result<(), Error> {
+        let conn = Self::get_connection().await;
+        sqlx::query(
+            r#"ALTER TABLE `notification_templates`
+                ADD COLUMN `digest` JSON NULL AFTER `category`
+                "#
+        )
+            .execute(&conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&mut self) -> Result<(), Error> {
+        // TODO sql
+        Ok(())
+    }
+
+    fn get_name(&mut self) -> String {
+        "Migration_digests000015".to_string()
+    }
+
+    fn new() -> impl MigrationTrait {
+        Self {}
+    }
+}
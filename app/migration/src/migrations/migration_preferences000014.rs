@@ -0,0 +1,47 @@
+use crate::migration::MigrationTrait;
+use core::error::Error;
+
+pub struct Migration {}
+
+impl MigrationTrait for Migration {
+    async fn up(&mut self) -> Result<(), Error> {
+        let conn = Self::get_connection().await;
+        sqlx::query(
+            r#"ALTER TABLE `notification_templates`
+                ADD COLUMN `category` VARCHAR(100) NULL AFTER `respect_quiet_hours`
+                "#
+        )
+            .execute(&conn)
+            .await?;
+
+        // A missing preference means the user receives the category.
+        sqlx::query(
+            r#"CREATE TABLE IF NOT EXISTS `user_preferences` (
+                `user_id` INTEGER NOT NULL,
+                `category` VARCHAR(100) NOT NULL,
+                `channel` VARCHAR(16) NOT NULL,
+                `enabled` BOOLEAN NOT NULL,
+                `update_date` DATETIME NOT NULL,
+                PRIMARY KEY (user_id, category, channel),
+                CONSTRAINT FK_user_id_user_preferences FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                )"#
+        )
+            .execute(&conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&mut self) -> Result<(), Error> {
+        // TODO sql
+        Ok(())
+    }
+
+    fn get_name(&mut self) -> String {
+        "Migration_preferences000014".to_string()
+    }
+
+    fn new() -> impl MigrationTrait {
+        Self {}
+    }
+}
@@ -0,0 +1,34 @@
+use crate::migration::MigrationTrait;
+use core::error::Error;
+
+pub struct Migration {}
+
+impl MigrationTrait for Migration {
+    async fn up(&mut self) -> Result<(), Error> {
+        let conn = Self::get_connection().await;
+        sqlx::query(
+            r#"ALTER TABLE `users`
+                ADD COLUMN `timezone` VARCHAR(64) NULL,
+                ADD COLUMN `quiet_hours_start` TIME NULL,
+                ADD COLUMN `quiet_hours_end` TIME NULL
+                "#
+        )
+            .execute(&conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&mut self) -> Result<(), Error> {
+        // TODO sql
+        Ok(())
+    }
+
+    fn get_name(&mut self) -> String {
+        "Migration_quiet_hours000001".to_string()
+    }
+
+    fn new() -> impl MigrationTrait {
+        Self {}
+    }
+}
@@ -0,0 +1,43 @@
+use crate::migration::MigrationTrait;
+use core::error::Error;
+
+pub struct Migration {}
+
+impl MigrationTrait for Migration {
+    async fn up(&mut self) -> Result<(), Error> {
+        let conn = Self::get_connection().await;
+        sqlx::query(
+            r#"CREATE TABLE IF NOT EXISTS `notification_schedules` (
+                `id` INTEGER NOT NULL auto_increment primary key,
+                `cron_expression` VARCHAR(255) NOT NULL,
+                `timezone` VARCHAR(64) NOT NULL,
+                `notification_type` VARCHAR(255) NOT NULL,
+                `user_ids` JSON NULL,
+                `extra_data` JSON NULL,
+                `template_data` JSON NULL,
+                `is_paused` BOOLEAN NOT NULL DEFAULT FALSE,
+                `next_run_date` DATETIME NOT NULL,
+                `last_run_date` DATETIME NULL,
+                `creation_date` DATETIME NOT NULL,
+                INDEX IDX_next_run_date_notification_schedules (is_paused, next_run_date)
+                )"#
+        )
+            .execute(&conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&mut self) -> Result<(), Error> {
+        // TODO sql
+        Ok(())
+    }
+
+    fn get_name(&mut self) -> String {
+        "Migration_schedules000002".to_string()
+    }
+
+    fn new() -> impl MigrationTrait {
+        Self {}
+    }
+}
@@ -0,0 +1,33 @@
+use crate::migration::MigrationTrait;
+use core::error::Error;
+
+pub struct Migration {}
+
+impl MigrationTrait for Migration {
+    async fn up(&mut self) -> Result<(), Error> {
+        let conn = Self::get_connection().await;
+        sqlx::query(
+            r#"ALTER TABLE `history_notifications`
+                ADD COLUMN `template_version` INTEGER NULL,
+                ADD COLUMN `template_locale` VARCHAR(35) NULL
+                "#
+        )
+            .execute(&conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&mut self) -> Result<(), Error> {
+        // TODO sql
+        Ok(())
+    }
+
+    fn get_name(&mut self) -> String {
+        "Migration_template_version000005".to_string()
+    }
+
+    fn new() -> impl MigrationTrait {
+        Self {}
+    }
+}
@@ -0,0 +1,45 @@
+use crate::migration::MigrationTrait;
+use core::error::Error;
+
+pub struct Migration {}
+
+impl MigrationTrait for Migration {
+    async fn up(&mut self) -> Result<(), Error> {
+        let conn = Self::get_connection().await;
+        // The default template of a key has an empty locale, so the unique index also covers it.
+        sqlx::query(
+            r#"CREATE TABLE IF NOT EXISTS `notification_templates` (
+                `id` INTEGER NOT NULL auto_increment primary key,
+                `key` VARCHAR(255) NOT NULL,
+                `locale` VARCHAR(35) NOT NULL DEFAULT '',
+                `title` TEXT NOT NULL,
+                `body` TEXT NOT NULL,
+                `params` JSON NULL,
+                `optional_params` JSON NULL,
+                `respect_quiet_hours` BOOLEAN NOT NULL DEFAULT FALSE,
+                `platform_overrides` JSON NULL,
+                `version` INTEGER NOT NULL DEFAULT 1,
+                `creation_date` DATETIME NOT NULL,
+                `update_date` DATETIME NOT NULL,
+                UNIQUE INDEX UNIQ_key_locale_notification_templates (`key`, `locale`)
+                )"#
+        )
+            .execute(&conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&mut self) -> Result<(), Error> {
+        // TODO sql
+        Ok(())
+    }
+
+    fn get_name(&mut self) -> String {
+        "Migration_templates000004".to_string()
+    }
+
+    fn new() -> impl MigrationTrait {
+        Self {}
+    }
+}
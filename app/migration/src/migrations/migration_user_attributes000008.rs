@@ -0,0 +1,49 @@
+use crate::migration::MigrationTrait;
+use core::error::Error;
+
+pub struct Migration {}
+
+impl MigrationTrait for Migration {
+    async fn up(&mut self) -> Result<(), Error> {
+        let conn = Self::get_connection().await;
+        sqlx::query(
+            r#"CREATE TABLE IF NOT EXISTS `user_attributes` (
+                `user_id` INTEGER NOT NULL,
+                `name` VARCHAR(64) NOT NULL,
+                `value` VARCHAR(255) NOT NULL,
+                PRIMARY KEY (user_id, name),
+                INDEX IDX_name_value_user_attributes (name, value),
+                CONSTRAINT FK_user_id_user_attributes FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                )"#
+        )
+            .execute(&conn)
+            .await?;
+
+        sqlx::query(
+            r#"CREATE TABLE IF NOT EXISTS `user_tags` (
+                `user_id` INTEGER NOT NULL,
+                `tag` VARCHAR(64) NOT NULL,
+                PRIMARY KEY (user_id, tag),
+                INDEX IDX_tag_user_tags (tag),
+                CONSTRAINT FK_user_id_user_tags FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                )"#
+        )
+            .execute(&conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&mut self) -> Result<(), Error> {
+        // TODO sql
+        Ok(())
+    }
+
+    fn get_name(&mut self) -> String {
+        "Migration_user_attributes000008".to_string()
+    }
+
+    fn new() -> impl MigrationTrait {
+        Self {}
+    }
+}
@@ -1,2 +1,18 @@
 pub mod migration_init000000;
-pub mod migration_base00000;
\ No newline at end of file
+pub mod migration_base00000;
+pub mod migration_quiet_hours000001;
+pub mod migration_schedules000002;
+pub mod migration_locale000003;
+pub mod migration_templates000004;
+pub mod migration_template_version000005;
+pub mod migration_template_options000006;
+pub mod migration_history_reason000007;
+pub mod migration_user_attributes000008;
+pub mod migration_campaigns000009;
+pub mod migration_campaign_rate000010;
+pub mod migration_api_keys000011;
+pub mod migration_apps000012;
+pub mod migration_device_registration000013;
+pub mod migration_preferences000014;
+pub mod migration_digests000015;
+pub mod migration_schedule_audience000016;
\ No newline at end of file
@@ -0,0 +1,174 @@
+use chrono::{DateTime, Utc};
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use sqlx::types::Json;
+use core::{
+    api_key_context::ApiKeyContext,
+    error::Error,
+    config::Config
+};
+use types::{
+    enums::ApiKeyScope,
+    identifier::Identifier
+};
+use crate::{
+    app_service::AppService,
+    rows::api_key_from_row::{ApiKeyFromRow, CountApiKeyFromRow}
+};
+
+pub type ListOfApiKeyFromRow = Vec<ApiKeyFromRow>;
+
+pub struct ApiKeyService {}
+
+impl ApiKeyService {
+    pub fn new() -> Self {
+        ApiKeyService {}
+    }
+}
+
+impl ApiKeyService {
+    const KEY_PREFIX: &'static str = "rpn_";
+    const KEY_BYTES: usize = 32;
+    const DISPLAYED_PREFIX_LENGTH: usize = 12;
+    const BOOTSTRAP_KEY_NAME: &'static str = "ADMIN_API_KEY";
+
+    // Returns the stored key with its plain value, which is never readable again.
+    pub async fn create(&mut self, app_id: i32, name: String, scopes: Vec<ApiKeyScope>) -> Result<(ApiKeyFromRow, String), Error> {
+        let mut bytes = [0u8; Self::KEY_BYTES];
+        OsRng.fill_bytes(&mut bytes);
+        let key = format!("{}{}", Self::KEY_PREFIX, hex::encode(bytes));
+
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query(
+            r#"
+                INSERT INTO `api_keys`
+                    (app_id, name, key_prefix, key_hash, scopes, creation_date)
+                    VALUES (?, ?, ?, ?, ?, NOW())
+            "#
+        )
+            .bind::<i32>(app_id)
+            .bind::<String>(name)
+            .bind::<String>(key[..Self::DISPLAYED_PREFIX_LENGTH].to_string())
+            .bind::<String>(Self::hash(&key))
+            .bind::<Json<Vec<String>>>(Json(scopes.iter().map(|scope| scope.to_string()).collect()))
+            .execute(&conn)
+            .await?;
+
+        let api_key = self.find_api_key_by_id(app_id, Identifier(result.last_insert_id() as i32)).await?;
+
+        Ok((api_key, key))
+    }
+
+    pub async fn find_api_key_by_id(&mut self, app_id: i32, api_key_id: Identifier) -> Result<ApiKeyFromRow, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_as::<_, ApiKeyFromRow>(
+            "SELECT * FROM `api_keys` WHERE id = ? AND app_id = ?"
+        )
+            .bind::<i32>(api_key_id.into())
+            .bind::<i32>(app_id)
+            .fetch_optional(&conn)
+            .await?;
+
+        match result {
+            Some(api_key) => Ok(api_key),
+            None => Err(Error::NotFoundError(format!("Api key with id {} does not exists", api_key_id.0)))
+        }
+    }
+
+    pub async fn fetch_api_key_paginated(&mut self, app_id: i32, offset: i32, limit: i32) -> Result<ListOfApiKeyFromRow, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_as::<_, ApiKeyFromRow>(
+            r#"
+                SELECT * FROM `api_keys`
+                WHERE app_id = ?
+                ORDER BY id DESC
+                LIMIT ?
+                OFFSET ?
+            "#
+        )
+            .bind::<i32>(app_id)
+            .bind::<i32>(limit)
+            .bind::<i32>(offset)
+            .fetch_all(&conn)
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn count(&mut self, app_id: i32) -> Result<i32, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_as::<_, CountApiKeyFromRow>(
+            "SELECT COUNT(*) as total_count FROM `api_keys` WHERE app_id = ?"
+        )
+            .bind::<i32>(app_id)
+            .fetch_one(&conn)
+            .await?;
+
+        Ok(result.total_count)
+    }
+
+    pub async fn revoke(&mut self, app_id: i32, api_key_id: Identifier) -> Result<(), Error> {
+        self.find_api_key_by_id(app_id, api_key_id).await?;
+
+        let conn = Config::get_database_conn().await?;
+        sqlx::query(
+            "UPDATE `api_keys` SET revocation_date = COALESCE(revocation_date, NOW()) WHERE id = ?"
+        )
+            .bind::<i32>(api_key_id.into())
+            .execute(&conn)
+            .await?;
+
+        Ok(())
+    }
+
+    // The bootstrap key acts on the requested app, other keys only on the app they belong to.
+    pub async fn authenticate(&mut self, key: &str, requested_app_id: Option<i32>) -> Result<ApiKeyContext, Error> {
+        let key_hash = Self::hash(key);
+        let is_bootstrap_key = Config::get_admin_api_key()
+            .is_some_and(|admin_api_key| Self::hash(&admin_api_key) == key_hash);
+        if is_bootstrap_key {
+            let app = AppService::new().find_app_by_id(requested_app_id.unwrap_or(AppService::DEFAULT_APP_ID).into()).await?;
+
+            return Ok(ApiKeyContext {
+                api_key_id: None,
+                app_id: app.id,
+                name: Self::BOOTSTRAP_KEY_NAME.to_string(),
+                scopes: Vec::from([ApiKeyScope::Admin])
+            })
+        }
+
+        let conn = Config::get_database_conn().await?;
+        let api_key = sqlx::query_as::<_, ApiKeyFromRow>(
+            "SELECT * FROM `api_keys` WHERE key_hash = ? AND revocation_date IS NULL"
+        )
+            .bind::<String>(key_hash)
+            .fetch_optional(&conn)
+            .await?;
+
+        let api_key = match api_key {
+            Some(api_key) => api_key,
+            None => return Err(Error::UnauthorizedError("Invalid api key".to_string()))
+        };
+        if requested_app_id.is_some_and(|app_id| app_id != api_key.app_id) {
+            return Err(Error::ForbiddenError("Api key does not belong to the requested app".to_string()))
+        }
+
+        sqlx::query("UPDATE `api_keys` SET last_used_date = ? WHERE id = ?")
+            .bind::<DateTime<Utc>>(Utc::now())
+            .bind::<i32>(api_key.id)
+            .execute(&conn)
+            .await?;
+
+        Ok(ApiKeyContext {
+            api_key_id: Some(api_key.id),
+            app_id: api_key.app_id,
+            name: api_key.name.clone(),
+            scopes: api_key.get_scopes()
+        })
+    }
+
+    // Plain keys are never stored, neither in the database nor in the rate limits.
+    pub fn hash(key: &str) -> String {
+        hex::encode(Sha256::digest(key.as_bytes()))
+    }
+}
\ No newline at end of file
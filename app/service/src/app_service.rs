@@ -0,0 +1,175 @@
+use std::path::Path;
+use core::{
+    error::Error,
+    config::Config
+};
+use types::identifier::Identifier;
+use crate::{
+    notification_catalog::NotificationCatalog,
+    rows::app_from_row::{AppFromRow, CountAppFromRow}
+};
+
+pub type ListOfAppFromRow = Vec<AppFromRow>;
+
+pub struct AppService {}
+
+impl AppService {
+    pub fn new() -> Self {
+        AppService {}
+    }
+}
+
+pub struct NotificationApp {
+    pub name: String,
+    pub google_service_account_credentials_path: Option<String>,
+    pub notification_resources_path: Option<String>,
+    pub device_registration_secret: Option<String>,
+    // Only read by updates, an absent secret keeps the stored one otherwise.
+    pub clear_device_registration_secret: bool
+}
+
+impl AppService {
+    // Created by the migrations, it owns every device registered before apps existed.
+    pub const DEFAULT_APP_ID: i32 = 1;
+    const MIN_DEVICE_REGISTRATION_SECRET_LENGTH: usize = 32;
+
+    pub async fn create(&mut self, app: NotificationApp) -> Result<AppFromRow, Error> {
+        Self::check_app(&app)?;
+        if self.find_app_by_name(&app.name).await?.is_some() {
+            return Err(Error::ValidationError(format!("App {} already exists", app.name)))
+        }
+
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query(
+            r#"
+                INSERT INTO `apps`
+                    (name, google_service_account_credentials_path, notification_resources_path, device_registration_secret, creation_date)
+                    VALUES (?, ?, ?, ?, NOW())
+            "#
+        )
+            .bind::<String>(app.name)
+            .bind::<Option<String>>(app.google_service_account_credentials_path)
+            .bind::<Option<String>>(app.notification_resources_path)
+            .bind::<Option<String>>(app.device_registration_secret)
+            .execute(&conn)
+            .await?;
+
+        self.find_app_by_id(Identifier(result.last_insert_id() as i32)).await
+    }
+
+    pub async fn find_app_by_id(&mut self, app_id: Identifier) -> Result<AppFromRow, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_as::<_, AppFromRow>(
+            "SELECT * FROM `apps` WHERE id = ?"
+        )
+            .bind::<i32>(app_id.into())
+            .fetch_optional(&conn)
+            .await?;
+
+        match result {
+            Some(app) => Ok(app),
+            None => Err(Error::NotFoundError(format!("App with id {} does not exists", app_id.0)))
+        }
+    }
+
+    pub async fn fetch_app_paginated(&mut self, offset: i32, limit: i32) -> Result<ListOfAppFromRow, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_as::<_, AppFromRow>(
+            r#"
+                SELECT * FROM `apps`
+                ORDER BY id ASC
+                LIMIT ?
+                OFFSET ?
+            "#
+        )
+            .bind::<i32>(limit)
+            .bind::<i32>(offset)
+            .fetch_all(&conn)
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn count(&mut self) -> Result<i32, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_as::<_, CountAppFromRow>(
+            "SELECT COUNT(*) as total_count FROM `apps`"
+        )
+            .fetch_one(&conn)
+            .await?;
+
+        Ok(result.total_count)
+    }
+
+    pub async fn update(&mut self, app_id: Identifier, app: NotificationApp) -> Result<AppFromRow, Error> {
+        let existing = self.find_app_by_id(app_id).await?;
+        Self::check_app(&app)?;
+        if self.find_app_by_name(&app.name).await?.is_some_and(|other| other.id != existing.id) {
+            return Err(Error::ValidationError(format!("App {} already exists", app.name)))
+        }
+
+        let conn = Config::get_database_conn().await?;
+        sqlx::query(
+            r#"
+                UPDATE `apps`
+                SET name = ?, google_service_account_credentials_path = ?, notification_resources_path = ?,
+                    device_registration_secret = IF(?, NULL, COALESCE(?, device_registration_secret))
+                WHERE id = ?
+            "#
+        )
+            .bind::<String>(app.name)
+            .bind::<Option<String>>(app.google_service_account_credentials_path)
+            .bind::<Option<String>>(app.notification_resources_path)
+            .bind::<bool>(app.clear_device_registration_secret)
+            .bind::<Option<String>>(app.device_registration_secret)
+            .bind::<i32>(app_id.into())
+            .execute(&conn)
+            .await?;
+
+        self.find_app_by_id(app_id).await
+    }
+}
+
+impl AppService {
+    async fn find_app_by_name(&mut self, name: &str) -> Result<Option<AppFromRow>, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_as::<_, AppFromRow>(
+            "SELECT * FROM `apps` WHERE name = ?"
+        )
+            .bind::<&str>(name)
+            .fetch_optional(&conn)
+            .await?;
+
+        Ok(result)
+    }
+
+    // Files are checked on the api side, the consumer would otherwise fail every notification of the app.
+    fn check_app(app: &NotificationApp) -> Result<(), Error> {
+        if app.name.trim().is_empty() {
+            return Err(Error::ValidationError("name cannot be empty".to_string()))
+        }
+
+        if let Some(path) = &app.google_service_account_credentials_path {
+            if !Path::new(path).is_file() {
+                return Err(Error::ValidationError(format!("google_service_account_credentials_path {} is not a readable file", path)))
+            }
+        }
+
+        if let Some(path) = &app.notification_resources_path {
+            if let Err(err) = NotificationCatalog::reload(path) {
+                return Err(Error::ValidationError(format!("notification_resources_path {}: {}", path, String::from(err))))
+            }
+        }
+
+        if let Some(secret) = &app.device_registration_secret {
+            if secret.len() < Self::MIN_DEVICE_REGISTRATION_SECRET_LENGTH {
+                return Err(Error::ValidationError(format!(
+                    "device_registration_secret must be at least {} characters long",
+                    Self::MIN_DEVICE_REGISTRATION_SECRET_LENGTH
+                )))
+            }
+        }
+
+        Ok(())
+    }
+}
\ No newline at end of file
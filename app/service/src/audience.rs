@@ -0,0 +1,386 @@
+use std::str::FromStr;
+use sqlx::{MySql, QueryBuilder};
+use core::error::Error;
+use types::enums::DeviceType;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Audience {
+    Tag(String),
+    Equals(String, String),
+    NotEquals(String, String),
+    Not(Box<Audience>),
+    And(Box<Audience>, Box<Audience>),
+    Or(Box<Audience>, Box<Audience>)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    OpenParenthesis,
+    CloseParenthesis,
+    Equals,
+    NotEquals
+}
+
+impl Audience {
+    pub const MAX_EXPRESSION_LENGTH: usize = 1024;
+    pub const MAX_NAME_LENGTH: usize = 64;
+    pub const MAX_VALUE_LENGTH: usize = 255;
+    // Device fields are read from the `users` table, every other field is a custom attribute.
+    pub const DEVICE_FIELDS: [&'static str; 3] = ["device_type", "locale", "timezone"];
+    const TAG_PREFIX: &'static str = "tag:";
+
+    // Parses expressions such as `device_type = Android AND (tag:beta OR plan != free)`.
+    pub fn parse(expression: &str) -> Result<Audience, Error> {
+        if expression.len() > Self::MAX_EXPRESSION_LENGTH {
+            return Err(Error::ValidationError(format!("audience cannot exceed {} characters", Self::MAX_EXPRESSION_LENGTH)))
+        }
+
+        let tokens = Self::tokenize(expression)?;
+        let mut position = 0;
+        let audience = Self::parse_or(&tokens, &mut position)?;
+        if position < tokens.len() {
+            return Err(Error::ValidationError(format!("Invalid audience: unexpected {}", Self::describe(&tokens[position]))))
+        }
+
+        Ok(audience)
+    }
+
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty()
+            && name.len() <= Self::MAX_NAME_LENGTH
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+    }
+
+    pub fn push_condition(&self, query_builder: &mut QueryBuilder<MySql>) {
+        match self {
+            Audience::Tag(tag) => {
+                query_builder.push("EXISTS (SELECT 1 FROM `user_tags` WHERE `user_tags`.user_id = `users`.id AND `user_tags`.tag = ");
+                query_builder.push_bind(tag.clone());
+                query_builder.push(")");
+            },
+            Audience::Equals(field, value) => Self::push_comparison(query_builder, field, value),
+            Audience::NotEquals(field, value) => {
+                query_builder.push("NOT ");
+                Self::push_comparison(query_builder, field, value);
+            },
+            Audience::Not(audience) => {
+                query_builder.push("NOT (");
+                audience.push_condition(query_builder);
+                query_builder.push(")");
+            },
+            Audience::And(left, right) => Self::push_binary(query_builder, left, " AND ", right),
+            Audience::Or(left, right) => Self::push_binary(query_builder, left, " OR ", right)
+        }
+    }
+}
+
+impl Audience {
+    fn push_comparison(query_builder: &mut QueryBuilder<MySql>, field: &str, value: &str) {
+        if Self::DEVICE_FIELDS.contains(&field) {
+            // Null-safe comparison, so `locale != fr` also matches devices without a locale.
+            query_builder.push(format!("(`users`.{} <=> ", field));
+            query_builder.push_bind(value.to_string());
+            query_builder.push(")");
+
+            return;
+        }
+
+        query_builder.push("EXISTS (SELECT 1 FROM `user_attributes` WHERE `user_attributes`.user_id = `users`.id AND `user_attributes`.name = ");
+        query_builder.push_bind(field.to_string());
+        query_builder.push(" AND `user_attributes`.value = ");
+        query_builder.push_bind(value.to_string());
+        query_builder.push(")");
+    }
+
+    fn push_binary(query_builder: &mut QueryBuilder<MySql>, left: &Audience, operator: &str, right: &Audience) {
+        query_builder.push("(");
+        left.push_condition(query_builder);
+        query_builder.push(operator);
+        right.push_condition(query_builder);
+        query_builder.push(")");
+    }
+
+    fn parse_or(tokens: &[Token], position: &mut usize) -> Result<Audience, Error> {
+        let mut audience = Self::parse_and(tokens, position)?;
+        while Self::next_is_keyword(tokens, *position, "OR") {
+            *position += 1;
+            audience = Audience::Or(Box::new(audience), Box::new(Self::parse_and(tokens, position)?));
+        }
+
+        Ok(audience)
+    }
+
+    fn parse_and(tokens: &[Token], position: &mut usize) -> Result<Audience, Error> {
+        let mut audience = Self::parse_not(tokens, position)?;
+        while Self::next_is_keyword(tokens, *position, "AND") {
+            *position += 1;
+            audience = Audience::And(Box::new(audience), Box::new(Self::parse_not(tokens, position)?));
+        }
+
+        Ok(audience)
+    }
+
+    fn parse_not(tokens: &[Token], position: &mut usize) -> Result<Audience, Error> {
+        if Self::next_is_keyword(tokens, *position, "NOT") {
+            *position += 1;
+            return Ok(Audience::Not(Box::new(Self::parse_not(tokens, position)?)))
+        }
+
+        Self::parse_primary(tokens, position)
+    }
+
+    fn parse_primary(tokens: &[Token], position: &mut usize) -> Result<Audience, Error> {
+        let token = match tokens.get(*position) {
+            Some(token) => token,
+            None => return Err(Error::ValidationError("Invalid audience: unexpected end of expression".to_string()))
+        };
+        *position += 1;
+
+        match token {
+            Token::OpenParenthesis => {
+                let audience = Self::parse_or(tokens, position)?;
+                match tokens.get(*position) {
+                    Some(Token::CloseParenthesis) => {
+                        *position += 1;
+                        Ok(audience)
+                    },
+                    _ => Err(Error::ValidationError("Invalid audience: missing closing parenthesis".to_string()))
+                }
+            },
+            Token::Word(word) if word.starts_with(Self::TAG_PREFIX) => {
+                let tag = &word[Self::TAG_PREFIX.len()..];
+                if !Self::is_valid_name(tag) {
+                    return Err(Error::ValidationError(format!("Invalid audience: invalid tag \"{}\"", tag)))
+                }
+
+                Ok(Audience::Tag(tag.to_string()))
+            },
+            Token::Word(field) if !Self::is_keyword(field) => {
+                if !Self::is_valid_name(field) {
+                    return Err(Error::ValidationError(format!("Invalid audience: invalid field \"{}\"", field)))
+                }
+
+                let is_equals = match tokens.get(*position) {
+                    Some(Token::Equals) => true,
+                    Some(Token::NotEquals) => false,
+                    _ => return Err(Error::ValidationError(format!("Invalid audience: expected = or != after \"{}\"", field)))
+                };
+                let value = match tokens.get(*position + 1) {
+                    Some(Token::Word(value)) | Some(Token::Quoted(value)) => Self::get_value(field, value)?,
+                    _ => return Err(Error::ValidationError(format!("Invalid audience: missing value for \"{}\"", field)))
+                };
+                *position += 2;
+
+                if is_equals {
+                    return Ok(Audience::Equals(field.clone(), value))
+                }
+
+                Ok(Audience::NotEquals(field.clone(), value))
+            },
+            token => Err(Error::ValidationError(format!("Invalid audience: unexpected {}", Self::describe(token))))
+        }
+    }
+
+    fn get_value(field: &str, value: &str) -> Result<String, Error> {
+        if value.len() > Self::MAX_VALUE_LENGTH {
+            return Err(Error::ValidationError(format!("Invalid audience: value of \"{}\" cannot exceed {} characters", field, Self::MAX_VALUE_LENGTH)))
+        }
+
+        if field != "device_type" {
+            return Ok(value.to_string())
+        }
+
+        // Device types are stored as their enum name (Android, IOS) while the api accepts iOS.
+        match DeviceType::from_str(value) {
+            Ok(device_type) => Ok(device_type.to_string()),
+            Err(_) if value == DeviceType::IOS.to_string() => Ok(value.to_string()),
+            Err(_) => Err(Error::ValidationError(format!("Invalid audience: unknown device_type \"{}\"", value)))
+        }
+    }
+
+    fn tokenize(expression: &str) -> Result<Vec<Token>, Error> {
+        let mut tokens = Vec::from([]);
+        let mut chars = expression.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                c if c.is_whitespace() => {},
+                '(' => tokens.push(Token::OpenParenthesis),
+                ')' => tokens.push(Token::CloseParenthesis),
+                '=' => tokens.push(Token::Equals),
+                '!' if chars.peek() == Some(&'=') => {
+                    chars.next();
+                    tokens.push(Token::NotEquals);
+                },
+                '"' => {
+                    let mut value = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some('\\') => match chars.next() {
+                                Some(escaped) => value.push(escaped),
+                                None => return Err(Error::ValidationError("Invalid audience: unterminated string".to_string()))
+                            },
+                            Some(c) => value.push(c),
+                            None => return Err(Error::ValidationError("Invalid audience: unterminated string".to_string()))
+                        }
+                    }
+                    tokens.push(Token::Quoted(value));
+                },
+                c if Self::is_word_char(c) => {
+                    let mut word = String::from(c);
+                    while let Some(&c) = chars.peek() {
+                        if !Self::is_word_char(c) {
+                            break;
+                        }
+                        word.push(c);
+                        chars.next();
+                    }
+                    tokens.push(Token::Word(word));
+                },
+                c => return Err(Error::ValidationError(format!("Invalid audience: unexpected character '{}'", c)))
+            }
+        }
+
+        if tokens.is_empty() {
+            return Err(Error::ValidationError("audience cannot be empty".to_string()))
+        }
+
+        Ok(tokens)
+    }
+
+    fn is_word_char(c: char) -> bool {
+        c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':' | '+' | '/')
+    }
+
+    fn is_keyword(word: &str) -> bool {
+        ["AND", "OR", "NOT"].iter().any(|keyword| word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn next_is_keyword(tokens: &[Token], position: usize, keyword: &str) -> bool {
+        matches!(tokens.get(position), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn describe(token: &Token) -> String {
+        match token {
+            Token::Word(word) => format!("\"{}\"", word),
+            Token::Quoted(value) => format!("\"{}\"", value),
+            Token::OpenParenthesis => "\"(\"".to_string(),
+            Token::CloseParenthesis => "\")\"".to_string(),
+            Token::Equals => "\"=\"".to_string(),
+            Token::NotEquals => "\"!=\"".to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(tag: &str) -> Box<Audience> {
+        Box::new(Audience::Tag(tag.to_string()))
+    }
+
+    fn equals(field: &str, value: &str) -> Box<Audience> {
+        Box::new(Audience::Equals(field.to_string(), value.to_string()))
+    }
+
+    fn assert_invalid(expression: &str) {
+        assert!(
+            matches!(Audience::parse(expression), Err(Error::ValidationError(_))),
+            "{} should be rejected", expression
+        );
+    }
+
+    #[test]
+    fn tokenize_splits_operators_words_and_quoted_values() {
+        let tokens = Audience::tokenize(r#"(tag:beta OR plan!="free \"tier\"")"#).unwrap();
+
+        assert_eq!(tokens, Vec::from([
+            Token::OpenParenthesis,
+            Token::Word("tag:beta".to_string()),
+            Token::Word("OR".to_string()),
+            Token::Word("plan".to_string()),
+            Token::NotEquals,
+            Token::Quoted(r#"free "tier""#.to_string()),
+            Token::CloseParenthesis
+        ]));
+    }
+
+    #[test]
+    fn tokenize_rejects_unterminated_strings_and_unknown_characters() {
+        assert!(Audience::tokenize(r#"plan = "free"#).is_err());
+        assert!(Audience::tokenize(r#"plan = "free\"#).is_err());
+        assert!(Audience::tokenize("plan = free;").is_err());
+        assert!(Audience::tokenize("plan ! free").is_err());
+        assert!(Audience::tokenize("   ").is_err());
+    }
+
+    #[test]
+    fn parse_reads_tags_and_comparisons() {
+        assert_eq!(Audience::parse("tag:beta").unwrap(), *tag("beta"));
+        assert_eq!(Audience::parse("plan = free").unwrap(), *equals("plan", "free"));
+        assert_eq!(
+            Audience::parse(r#"city != "New York""#).unwrap(),
+            Audience::NotEquals("city".to_string(), "New York".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_stores_device_types_as_their_enum_name() {
+        assert_eq!(Audience::parse("device_type = Android").unwrap(), *equals("device_type", "Android"));
+        assert_eq!(Audience::parse("device_type = iOS").unwrap(), *equals("device_type", "IOS"));
+        assert_eq!(Audience::parse("device_type = IOS").unwrap(), *equals("device_type", "IOS"));
+        assert_invalid("device_type = Windows");
+    }
+
+    #[test]
+    fn parse_binds_and_tighter_than_or() {
+        assert_eq!(
+            Audience::parse("tag:a OR tag:b AND tag:c").unwrap(),
+            Audience::Or(tag("a"), Box::new(Audience::And(tag("b"), tag("c"))))
+        );
+        assert_eq!(
+            Audience::parse("(tag:a OR tag:b) and tag:c").unwrap(),
+            Audience::And(Box::new(Audience::Or(tag("a"), tag("b"))), tag("c"))
+        );
+    }
+
+    #[test]
+    fn parse_binds_not_to_the_closest_operand() {
+        assert_eq!(
+            Audience::parse("NOT tag:a AND tag:b").unwrap(),
+            Audience::And(Box::new(Audience::Not(tag("a"))), tag("b"))
+        );
+        assert_eq!(
+            Audience::parse("not not (tag:a OR tag:b)").unwrap(),
+            Audience::Not(Box::new(Audience::Not(Box::new(Audience::Or(tag("a"), tag("b"))))))
+        );
+    }
+
+    #[test]
+    fn parse_rejects_malformed_expressions() {
+        assert_invalid("");
+        assert_invalid("tag:");
+        assert_invalid("tag:a AND");
+        assert_invalid("NOT");
+        assert_invalid("(tag:a OR tag:b");
+        assert_invalid("tag:a)");
+        assert_invalid("tag:a tag:b");
+        assert_invalid("plan");
+        assert_invalid("plan =");
+        assert_invalid("plan = = free");
+        assert_invalid("AND = free");
+        assert_invalid("= free");
+        assert_invalid("pl@n = free");
+    }
+
+    #[test]
+    fn parse_rejects_oversized_input() {
+        assert_invalid(&format!("tag:{}", "a".repeat(Audience::MAX_EXPRESSION_LENGTH)));
+        assert_invalid(&format!("tag:{}", "a".repeat(Audience::MAX_NAME_LENGTH + 1)));
+        assert_invalid(&format!("plan = {}", "a".repeat(Audience::MAX_VALUE_LENGTH + 1)));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,400 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+use core::{
+    error::Error,
+    config::Config
+};
+use types::{
+    enums::{CampaignStatus, NotificationStatus},
+    extra_data::ExtraData,
+    identifier::Identifier
+};
+use crate::{
+    audience::Audience,
+    notification_options::NotificationOptions,
+    notification_rate::NotificationRate,
+    redis_service::RedisService,
+    user_service::UserService,
+    rows::{
+        notification_row::NotificationRow,
+        campaign_from_row::{CampaignFromRow, CampaignStatusCountFromRow, CountCampaignFromRow}
+    }
+};
+
+pub type ListOfCampaignFromRow = Vec<CampaignFromRow>;
+
+pub struct CampaignService {
+    redis_service: RedisService,
+    user_service: UserService
+}
+
+impl CampaignService {
+    pub fn new() -> Self {
+        CampaignService {
+            redis_service: RedisService::new(),
+            user_service: UserService::new()
+        }
+    }
+}
+
+pub struct NotificationCampaign {
+    pub name: String,
+    pub notification_type: String,
+    pub audience: Option<String>,
+    pub extra_data: Option<ExtraData>,
+    pub template_data: Option<HashMap<String, String>>,
+    pub options: NotificationOptions,
+    pub rate: Option<NotificationRate>,
+    pub scheduled_date: Option<DateTime<Utc>>
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CampaignProgress {
+    pub queued: i32,
+    pub sent: i32,
+    pub failed: i32,
+    pub canceled: i32,
+    pub pending: i32
+}
+
+impl CampaignService {
+    const CHUNK_SIZE: i32 = 1000;
+    // Campaigns with a rate keep at most this many seconds worth of notifications waiting to be sent.
+    const RATED_BACKLOG_SECONDS: i64 = 30;
+
+    pub async fn create(&mut self, app_id: i32, campaign: NotificationCampaign) -> Result<CampaignFromRow, Error> {
+        if let Some(audience) = &campaign.audience {
+            Audience::parse(audience)?;
+        }
+        if let Some(rate) = &campaign.rate {
+            rate.validate()?;
+        }
+
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query(
+            r#"
+                INSERT INTO `campaigns`
+                    (app_id, name, notification_type, audience, extra_data, template_data, options, rate, status, scheduled_date, creation_date)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, NOW())
+            "#
+        )
+            .bind::<i32>(app_id)
+            .bind::<String>(campaign.name)
+            .bind::<String>(campaign.notification_type)
+            .bind::<Option<String>>(campaign.audience)
+            .bind::<Option<Json<ExtraData>>>(campaign.extra_data.map(Json))
+            .bind::<Option<Json<HashMap<String, String>>>>(campaign.template_data.map(Json))
+            .bind::<Json<NotificationOptions>>(Json(campaign.options))
+            .bind::<Option<Json<NotificationRate>>>(campaign.rate.map(Json))
+            .bind::<String>(CampaignStatus::Draft.into())
+            .bind::<Option<DateTime<Utc>>>(campaign.scheduled_date)
+            .execute(&conn)
+            .await?;
+
+        self.find_campaign_by_id(app_id, Identifier(result.last_insert_id() as i32)).await
+    }
+
+    // Created and started at once, for the sends handing their fan-out over to a campaign.
+    pub async fn launch(&mut self, app_id: i32, campaign: NotificationCampaign) -> Result<CampaignFromRow, Error> {
+        let campaign = self.create(app_id, campaign).await?;
+
+        self.start(app_id, Identifier(campaign.id)).await
+    }
+
+    pub async fn find_campaign_by_id(&mut self, app_id: i32, campaign_id: Identifier) -> Result<CampaignFromRow, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_as::<_, CampaignFromRow>(
+            "SELECT * FROM `campaigns` WHERE id = ? AND app_id = ?"
+        )
+            .bind::<i32>(campaign_id.into())
+            .bind::<i32>(app_id)
+            .fetch_optional(&conn)
+            .await?;
+
+        match result {
+            Some(campaign) => Ok(campaign),
+            None => Err(Error::NotFoundError(format!("Campaign with id {} does not exists", campaign_id.0)))
+        }
+    }
+
+    pub async fn fetch_campaign_paginated(&mut self, app_id: i32, offset: i32, limit: i32) -> Result<ListOfCampaignFromRow, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_as::<_, CampaignFromRow>(
+            r#"
+                SELECT * FROM `campaigns`
+                WHERE app_id = ?
+                ORDER BY id DESC
+                LIMIT ?
+                OFFSET ?
+            "#
+        )
+            .bind::<i32>(app_id)
+            .bind::<i32>(limit)
+            .bind::<i32>(offset)
+            .fetch_all(&conn)
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn count(&mut self, app_id: i32) -> Result<i32, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_as::<_, CountCampaignFromRow>(
+            "SELECT COUNT(*) as total_count FROM `campaigns` WHERE app_id = ?"
+        )
+            .bind::<i32>(app_id)
+            .fetch_one(&conn)
+            .await?;
+
+        Ok(result.total_count)
+    }
+
+    pub async fn get_progress(&mut self, campaign: &CampaignFromRow) -> Result<CampaignProgress, Error> {
+        let conn = Config::get_database_conn().await?;
+        let counts = sqlx::query_as::<_, CampaignStatusCountFromRow>(
+            r#"
+                SELECT status, COUNT(*) as total_count FROM `history_notifications`
+                WHERE campaign_id = ?
+                GROUP BY status
+            "#
+        )
+            .bind::<i32>(campaign.id)
+            .fetch_all(&conn)
+            .await?;
+
+        let mut progress = CampaignProgress { queued: campaign.queued_count, ..Default::default() };
+        for count in &counts {
+            match NotificationStatus::from_str(&count.status) {
+                Ok(NotificationStatus::Sent) => progress.sent = count.total_count,
+                Ok(NotificationStatus::Failed) => progress.failed = count.total_count,
+                Ok(NotificationStatus::Canceled) => progress.canceled = count.total_count,
+                _ => {}
+            }
+        }
+        progress.pending = (progress.queued - progress.sent - progress.failed - progress.canceled).max(0);
+
+        Ok(progress)
+    }
+
+    pub async fn start(&mut self, app_id: i32, campaign_id: Identifier) -> Result<CampaignFromRow, Error> {
+        let campaign = self.find_campaign_by_id(app_id, campaign_id).await?;
+        if campaign.get_status() != CampaignStatus::Draft {
+            return Err(Error::ValidationError(format!("Campaign is {} and cannot be started", campaign.status)))
+        }
+
+        let now = Utc::now();
+        let status = match campaign.scheduled_date {
+            Some(scheduled_date) if scheduled_date > now => CampaignStatus::Scheduled,
+            _ => CampaignStatus::Running
+        };
+        self.update_status(campaign.id, status, now).await?;
+
+        self.find_campaign_by_id(app_id, campaign_id).await
+    }
+
+    pub async fn cancel(&mut self, app_id: i32, campaign_id: Identifier) -> Result<CampaignFromRow, Error> {
+        let campaign = self.find_campaign_by_id(app_id, campaign_id).await?;
+        if matches!(campaign.get_status(), CampaignStatus::Completed | CampaignStatus::Canceled) {
+            return Err(Error::ValidationError(format!("Campaign is {} and cannot be canceled", campaign.status)))
+        }
+
+        self.update_status(campaign.id, CampaignStatus::Canceled, Utc::now()).await?;
+
+        self.find_campaign_by_id(app_id, campaign_id).await
+    }
+
+    pub async fn pause(&mut self, app_id: i32, campaign_id: Identifier) -> Result<CampaignFromRow, Error> {
+        let campaign = self.find_campaign_by_id(app_id, campaign_id).await?;
+        if !matches!(campaign.get_status(), CampaignStatus::Scheduled | CampaignStatus::Running) {
+            return Err(Error::ValidationError(format!("Campaign is {} and cannot be paused", campaign.status)))
+        }
+
+        self.update_status(campaign.id, CampaignStatus::Paused, Utc::now()).await?;
+
+        self.find_campaign_by_id(app_id, campaign_id).await
+    }
+
+    pub async fn resume(&mut self, app_id: i32, campaign_id: Identifier) -> Result<CampaignFromRow, Error> {
+        let campaign = self.find_campaign_by_id(app_id, campaign_id).await?;
+        if campaign.get_status() != CampaignStatus::Paused {
+            return Err(Error::ValidationError(format!("Campaign is {} and cannot be resumed", campaign.status)))
+        }
+
+        let now = Utc::now();
+        let status = match campaign.scheduled_date {
+            Some(scheduled_date) if scheduled_date > now => CampaignStatus::Scheduled,
+            _ => CampaignStatus::Running
+        };
+        self.update_status(campaign.id, status, now).await?;
+
+        self.find_campaign_by_id(app_id, campaign_id).await
+    }
+
+    // A deleted campaign is reported as canceled, nobody is left to deliver it to.
+    pub async fn find_campaign_status(&mut self, campaign_id: i32) -> Result<CampaignStatus, Error> {
+        let conn = Config::get_database_conn().await?;
+        let status = sqlx::query_scalar::<_, String>(
+            "SELECT status FROM `campaigns` WHERE id = ?"
+        )
+            .bind::<i32>(campaign_id)
+            .fetch_optional(&conn)
+            .await?;
+
+        match status.map(|status| CampaignStatus::from_str(&status)) {
+            Some(Ok(status)) => Ok(status),
+            _ => Ok(CampaignStatus::Canceled)
+        }
+    }
+
+    // Enqueues the next chunk of every running campaign, one chunk per campaign and per call.
+    pub async fn run_campaigns(&mut self) -> Result<usize, Error> {
+        let now = Utc::now();
+        let conn = Config::get_database_conn().await?;
+        sqlx::query(
+            r#"
+                UPDATE `campaigns` SET status = ?, start_date = COALESCE(start_date, ?)
+                WHERE status = ? AND scheduled_date <= ?
+            "#
+        )
+            .bind::<String>(CampaignStatus::Running.into())
+            .bind::<DateTime<Utc>>(now)
+            .bind::<String>(CampaignStatus::Scheduled.into())
+            .bind::<DateTime<Utc>>(now)
+            .execute(&conn)
+            .await?;
+
+        let campaigns = sqlx::query_as::<_, CampaignFromRow>(
+            "SELECT * FROM `campaigns` WHERE status = ?"
+        )
+            .bind::<String>(CampaignStatus::Running.into())
+            .fetch_all(&conn)
+            .await?;
+
+        let mut enqueued = 0;
+        for campaign in &campaigns {
+            enqueued += self.enqueue_next_chunk(campaign).await?;
+        }
+
+        Ok(enqueued)
+    }
+}
+
+impl CampaignService {
+    async fn update_status(&mut self, campaign_id: i32, status: CampaignStatus, now: DateTime<Utc>) -> Result<(), Error> {
+        let conn = Config::get_database_conn().await?;
+        sqlx::query(
+            r#"
+                UPDATE `campaigns`
+                SET status = ?,
+                    start_date = IF(? = ?, COALESCE(start_date, ?), start_date),
+                    completion_date = IF(? = ?, ?, completion_date)
+                WHERE id = ?
+            "#
+        )
+            .bind::<String>(status.into())
+            .bind::<String>(status.into())
+            .bind::<String>(CampaignStatus::Running.into())
+            .bind::<DateTime<Utc>>(now)
+            .bind::<String>(status.into())
+            .bind::<String>(CampaignStatus::Canceled.into())
+            .bind::<DateTime<Utc>>(now)
+            .bind::<i32>(campaign_id)
+            .execute(&conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn enqueue_next_chunk(&mut self, campaign: &CampaignFromRow) -> Result<usize, Error> {
+        let chunk_size = self.get_chunk_size(campaign).await?;
+        if chunk_size == 0 {
+            return Ok(0)
+        }
+
+        let user_ids = match &campaign.audience {
+            Some(audience) => self.user_service.fetch_audience_user_ids_after(
+                campaign.app_id,
+                &Audience::parse(audience)?,
+                campaign.last_user_id,
+                chunk_size
+            ).await?,
+            None => self.user_service.fetch_user_ids_after(campaign.app_id, campaign.last_user_id, chunk_size).await?
+        };
+        let is_last_chunk = (user_ids.len() as i32) < chunk_size;
+
+        // The campaign stays locked until its counters move, so two consumers never enqueue the same chunk.
+        let conn = Config::get_database_conn().await?;
+        let mut transaction = conn.begin().await?;
+        let claimed = sqlx::query_scalar::<_, i32>(
+            "SELECT id FROM `campaigns` WHERE id = ? AND status = ? AND last_user_id = ? FOR UPDATE"
+        )
+            .bind::<i32>(campaign.id)
+            .bind::<String>(CampaignStatus::Running.into())
+            .bind::<i32>(campaign.last_user_id)
+            .fetch_optional(&mut *transaction)
+            .await?;
+
+        if claimed.is_none() {
+            return Ok(0)
+        }
+
+        // Enqueued before the counters move, a failing chunk is rolled back and retried at the next cycle.
+        let notifications: Vec<NotificationRow> = user_ids.iter()
+            .map(|user_id| Self::get_notification(campaign, *user_id))
+            .collect();
+        self.redis_service.create_notifications(&notifications).await?;
+
+        sqlx::query(
+            r#"
+                UPDATE `campaigns`
+                SET last_user_id = ?,
+                    queued_count = queued_count + ?,
+                    status = ?,
+                    completion_date = ?
+                WHERE id = ?
+            "#
+        )
+            .bind::<i32>(user_ids.last().copied().unwrap_or(campaign.last_user_id))
+            .bind::<i32>(user_ids.len() as i32)
+            .bind::<String>(if is_last_chunk { CampaignStatus::Completed.into() } else { CampaignStatus::Running.into() })
+            .bind::<Option<DateTime<Utc>>>(if is_last_chunk { Some(Utc::now()) } else { None })
+            .bind::<i32>(campaign.id)
+            .execute(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+
+        Ok(user_ids.len())
+    }
+
+    // The queue of a rated campaign only grows as fast as the rate drains it, leaving room to the other notifications.
+    async fn get_chunk_size(&mut self, campaign: &CampaignFromRow) -> Result<i32, Error> {
+        let Some(rate) = &campaign.rate else {
+            return Ok(Self::CHUNK_SIZE)
+        };
+
+        // Notifications held by quiet hours would otherwise stall the campaign until the end of the night.
+        let deferred = self.redis_service.count_deferred_campaign_notifications(campaign.id).await?;
+        let pending = (self.get_progress(campaign).await?.pending as i64 - deferred).max(0);
+        let backlog = rate.0.get_messages_over(Self::RATED_BACKLOG_SECONDS * 1_000);
+
+        Ok((backlog - pending).clamp(0, Self::CHUNK_SIZE as i64) as i32)
+    }
+
+    fn get_notification(campaign: &CampaignFromRow, user_id: i32) -> NotificationRow {
+        NotificationRow {
+            app_id: campaign.app_id,
+            user_id,
+            notification_type: campaign.notification_type.clone(),
+            number_of_tries: 0,
+            extra_data: campaign.extra_data.as_ref().map(|extra_data| extra_data.0.clone()),
+            template_data: campaign.template_data.as_ref().map(|template_data| serde_json::to_string(&template_data.0).unwrap()),
+            options: campaign.get_options(),
+            creation_date: Some(Utc::now()),
+            campaign_id: Some(campaign.id),
+            throttle: campaign.get_throttle(),
+            digest: None,
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,112 @@
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use core::error::Error;
+use types::device_id::DeviceId;
+
+// Proof sent by a device on registration, issued by the backend of the app :
+// `<expires_at>.<hex hmac_sha256(secret, "<device_id>:<expires_at>")>`, expires_at being a unix timestamp.
+#[derive(Clone, Debug)]
+pub struct DeviceSignature {
+    expires_at: i64,
+    signature: Vec<u8>
+}
+
+impl DeviceSignature {
+    // Keeps a leaked signature from being replayed for long.
+    pub const MAX_LIFETIME_IN_SECONDS: i64 = 3600;
+
+    pub fn parse(value: &str) -> Result<Self, Error> {
+        let invalid = || Error::UnauthorizedError("Invalid device signature".to_string());
+
+        let (expires_at, signature) = value.trim().split_once('.').ok_or_else(invalid)?;
+        let expires_at = expires_at.parse::<i64>().map_err(|_| invalid())?;
+        let signature = hex::decode(signature).map_err(|_| invalid())?;
+
+        Ok(DeviceSignature { expires_at, signature })
+    }
+
+    pub fn verify(&self, secret: &str, device_id: &DeviceId) -> Result<(), Error> {
+        let now = Utc::now().timestamp();
+        if self.expires_at < now {
+            return Err(Error::UnauthorizedError("Expired device signature".to_string()))
+        }
+
+        if self.expires_at > now + Self::MAX_LIFETIME_IN_SECONDS {
+            return Err(Error::UnauthorizedError(format!(
+                "Device signature cannot be valid for more than {} seconds",
+                Self::MAX_LIFETIME_IN_SECONDS
+            )))
+        }
+
+        // verify_slice compares in constant time.
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .map_err(|_| Error::ConfigError("Invalid device registration secret".to_string()))?;
+        mac.update(format!("{}:{}", device_id.0, self.expires_at).as_bytes());
+        mac.verify_slice(&self.signature)
+            .map_err(|_| Error::UnauthorizedError("Invalid device signature".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "a-device-registration-secret-of-32-chars";
+
+    fn sign(secret: &str, device_id: &str, expires_at: i64) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("{}:{}", device_id, expires_at).as_bytes());
+
+        format!("{}.{}", expires_at, hex::encode(mac.finalize().into_bytes()))
+    }
+
+    fn verify(value: &str, device_id: &str) -> Result<(), Error> {
+        DeviceSignature::parse(value)?.verify(SECRET, &DeviceId(device_id.to_string()))
+    }
+
+    #[test]
+    fn parse_rejects_malformed_values() {
+        for value in ["", "1700000000", "soon.abcd", "1700000000.not-hex", "1700000000.abc"] {
+            assert!(matches!(DeviceSignature::parse(value), Err(Error::UnauthorizedError(_))), "{} should be rejected", value);
+        }
+    }
+
+    #[test]
+    fn verify_accepts_a_valid_signature() {
+        let value = sign(SECRET, "device", Utc::now().timestamp() + 300);
+
+        assert!(verify(&value, "device").is_ok());
+        assert!(verify(&format!(" {} ", value), "device").is_ok());
+    }
+
+    #[test]
+    fn verify_rejects_a_bad_mac() {
+        let expires_at = Utc::now().timestamp() + 300;
+
+        assert!(matches!(verify(&sign(SECRET, "device", expires_at), "other-device"), Err(Error::UnauthorizedError(_))));
+        assert!(matches!(verify(&sign("another-secret-of-at-least-32-chars", "device", expires_at), "device"), Err(Error::UnauthorizedError(_))));
+        // The signed expiry cannot be extended.
+        let value = sign(SECRET, "device", expires_at);
+        let (_, signature) = value.split_once('.').unwrap();
+        assert!(matches!(verify(&format!("{}.{}", expires_at + 60, signature), "device"), Err(Error::UnauthorizedError(_))));
+    }
+
+    #[test]
+    fn verify_rejects_an_expired_signature() {
+        let value = sign(SECRET, "device", Utc::now().timestamp() - 1);
+
+        assert!(matches!(verify(&value, "device"), Err(Error::UnauthorizedError(message)) if message.contains("Expired")));
+    }
+
+    #[test]
+    fn verify_rejects_a_lifetime_over_an_hour() {
+        let now = Utc::now().timestamp();
+
+        assert!(verify(&sign(SECRET, "device", now + DeviceSignature::MAX_LIFETIME_IN_SECONDS - 5), "device").is_ok());
+        assert!(matches!(
+            verify(&sign(SECRET, "device", now + DeviceSignature::MAX_LIFETIME_IN_SECONDS + 5), "device"),
+            Err(Error::UnauthorizedError(message)) if message.contains("3600")
+        ));
+    }
+}
\ No newline at end of file
@@ -2,4 +2,15 @@ pub mod user_service;
 pub mod notification_history_service;
 pub mod rows;
 pub mod redis_service;
-pub mod notification_service;
\ No newline at end of file
+pub mod notification_service;
+pub mod schedule_service;
+pub mod template_renderer;
+pub mod notification_template_service;
+pub mod notification_catalog;
+pub mod notification_options;
+pub mod audience;
+pub mod campaign_service;
+pub mod notification_rate;
+pub mod api_key_service;
+pub mod app_service;
+pub mod device_signature;
\ No newline at end of file
@@ -0,0 +1,116 @@
+use std::{
+    collections::HashMap,
+    fs,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock, RwLock
+    },
+    thread,
+    time::{Duration, SystemTime}
+};
+use core::{
+    error::Error,
+    config::Config
+};
+use crate::notification_template_service::NotificationsRule;
+
+// Catalogs by notifications file path, every app may bring its own file.
+static CATALOGS: OnceLock<RwLock<HashMap<String, Arc<NotificationsRule>>>> = OnceLock::new();
+
+pub struct NotificationCatalog {}
+
+impl NotificationCatalog {
+    const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+    pub fn init() -> Result<(), Error> {
+        Self::get()?;
+
+        Ok(())
+    }
+
+    pub fn get() -> Result<Arc<NotificationsRule>, Error> {
+        Self::get_by_path(&Config::get_notification_resources_path()?)
+    }
+
+    pub fn get_by_path(path: &str) -> Result<Arc<NotificationsRule>, Error> {
+        let catalogs = Self::get_catalogs();
+        if let Some(catalog) = catalogs.read().unwrap().get(path) {
+            return Ok(catalog.clone());
+        }
+
+        let catalog = Arc::new(Self::load(path)?);
+        catalogs.write().unwrap().insert(path.to_string(), catalog.clone());
+
+        Ok(catalog)
+    }
+
+    pub fn reload(path: &str) -> Result<(), Error> {
+        let catalog = Self::load(path)?;
+        Self::get_catalogs().write().unwrap().insert(path.to_string(), Arc::new(catalog));
+
+        Ok(())
+    }
+
+    pub fn watch() -> Result<(), Error> {
+        let reload_requested = Arc::new(AtomicBool::new(false));
+        #[cfg(unix)]
+        if let Err(err) = signal_hook::flag::register(signal_hook::consts::SIGHUP, reload_requested.clone()) {
+            return Err(Error::ConfigError(format!("Could not listen to SIGHUP: {}", err)));
+        }
+
+        thread::spawn(move || {
+            let mut last_modification_dates: HashMap<String, Option<SystemTime>> = HashMap::new();
+            loop {
+                thread::sleep(Self::WATCH_INTERVAL);
+
+                let reload_all = reload_requested.swap(false, Ordering::Relaxed);
+                let paths: Vec<String> = Self::get_catalogs().read().unwrap().keys().cloned().collect();
+                for path in paths {
+                    let modification_date = Self::get_modification_date(&path);
+                    // A catalog seen for the first time was just loaded, its date is only recorded.
+                    let file_changed = last_modification_dates.insert(path.clone(), modification_date)
+                        .is_some_and(|last_modification_date| last_modification_date != modification_date);
+                    if !reload_all && !file_changed {
+                        continue;
+                    }
+
+                    match Self::reload(&path) {
+                        Ok(_) => println!("Notifications catalog {} reloaded", path),
+                        Err(err) => println!("Notifications catalog {} reload rejected, keeping the previous one: {}", path, String::from(err))
+                    }
+                }
+            }
+        });
+
+        Ok(())
+    }
+}
+
+impl NotificationCatalog {
+    fn get_catalogs() -> &'static RwLock<HashMap<String, Arc<NotificationsRule>>> {
+        CATALOGS.get_or_init(|| RwLock::new(HashMap::new()))
+    }
+
+    fn load(path: &str) -> Result<NotificationsRule, Error> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) => return Err(Error::ConfigError(format!("Could not read notifications file: {}", err)))
+        };
+
+        let catalog = match serde_yaml::from_str::<NotificationsRule>(&contents) {
+            Ok(catalog) => catalog,
+            Err(err) => return Err(Error::ConfigError(format!("Could not parse notifications file: {}", err)))
+        };
+        if let Err(err) = catalog.validate() {
+            return Err(Error::ConfigError(format!("Invalid notifications file: {}", String::from(err))));
+        }
+
+        Ok(catalog)
+    }
+
+    fn get_modification_date(path: &str) -> Option<SystemTime> {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+}
\ No newline at end of file
@@ -5,7 +5,7 @@ use core::{
 };
 use types::{
     dates::{CreationDate, UpdateDate},
-    enums::{NotificationStatus, NotificationType},
+    enums::NotificationStatus,
     identifier::Identifier
 };
 use crate::rows::notification_row::NotificationRow;
@@ -19,9 +19,14 @@ impl NotificationHistoryService {
 }
 
 pub struct NotificationHistory {
+    pub app_id: i32,
     pub user_id: Identifier,
-    pub notification_type: NotificationType,
+    pub notification_type: String,
     pub notification_status: NotificationStatus,
+    pub template_version: Option<i32>,
+    pub template_locale: Option<String>,
+    pub reason: Option<String>,
+    pub campaign_id: Option<i32>,
     pub creation_date: CreationDate,
     pub update_date: UpdateDate
 }
@@ -29,11 +34,16 @@ pub struct NotificationHistory {
 impl From<&NotificationRow> for NotificationHistory {
     fn from(notification: &NotificationRow) -> Self {
         NotificationHistory {
+            app_id: notification.app_id,
             user_id: notification.clone().user_id.into(),
             notification_status: NotificationStatus::Failed,
+            template_version: None,
+            template_locale: None,
+            reason: None,
+            campaign_id: notification.campaign_id,
             update_date: UpdateDate { 0: Utc::now() },
             creation_date: CreationDate { 0: Utc::now()},
-            notification_type: notification.clone().notification_type.into()
+            notification_type: notification.clone().notification_type
         }
     }
 }
@@ -44,15 +54,20 @@ impl NotificationHistoryService {
         sqlx::query(
             r#"
                 INSERT INTO `history_notifications`
-                    (owner, creation_date, update_date, notification_type, status)
-                    VALUES (?, ?, ?, ?, ?)
+                    (app_id, owner, creation_date, update_date, notification_type, status, template_version, template_locale, reason, campaign_id)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#
         )
+            .bind::<i32>(notification.app_id)
             .bind::<String>(notification.user_id.into())
             .bind::<chrono::DateTime<Utc>>(notification.creation_date.0)
             .bind::<chrono::DateTime<Utc>>(notification.update_date.0)
-            .bind::<String>(notification.notification_type.into())
+            .bind::<String>(notification.notification_type)
             .bind::<String>(notification.notification_status.into())
+            .bind::<Option<i32>>(notification.template_version)
+            .bind::<Option<String>>(notification.template_locale)
+            .bind::<Option<String>>(notification.reason)
+            .bind::<Option<i32>>(notification.campaign_id)
             .execute(&conn)
             .await?;
 
@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use core::error::Error;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct NotificationOptions {
+    pub silent: Option<bool>,
+    pub priority: Option<String>,
+    pub ttl: Option<i64>,
+    pub collapse_key: Option<String>,
+    pub image: Option<String>,
+    pub sound: Option<String>,
+    pub badge: Option<i32>,
+    pub click_action: Option<String>,
+    // Notifications of a user sharing a dedup key within the dedup window are only sent once.
+    pub dedup_key: Option<String>,
+    // Derives the dedup key from the notification type and template data when none is given, false turns it off.
+    pub deduplicate: Option<bool>,
+    pub dedup_window: Option<i64>,
+    pub android: Option<AndroidNotificationOptions>,
+    pub ios: Option<IosNotificationOptions>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct AndroidNotificationOptions {
+    pub channel_id: Option<String>,
+    pub icon: Option<String>,
+    pub color: Option<String>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct IosNotificationOptions {
+    pub category: Option<String>,
+    pub thread_id: Option<String>
+}
+
+impl NotificationOptions {
+    pub const HIGH_PRIORITY: &'static str = "high";
+    pub const NORMAL_PRIORITY: &'static str = "normal";
+    const MAX_TTL: i64 = 2_419_200;
+    const MAX_COLLAPSE_KEY_LENGTH: usize = 64;
+    const MAX_DEDUP_KEY_LENGTH: usize = 255;
+    const DEFAULT_DEDUP_WINDOW: i64 = 300;
+    const MAX_DEDUP_WINDOW: i64 = 86_400;
+
+    pub fn validate(&self) -> Result<(), Error> {
+        if let Some(priority) = &self.priority {
+            if priority != Self::HIGH_PRIORITY && priority != Self::NORMAL_PRIORITY {
+                return Err(Error::ValidationError("priority must be high or normal".to_string()));
+            }
+        }
+
+        if self.ttl.is_some_and(|ttl| !(0..=Self::MAX_TTL).contains(&ttl)) {
+            return Err(Error::ValidationError(format!("ttl must be between 0 and {} seconds", Self::MAX_TTL)));
+        }
+
+        if self.collapse_key.as_ref().is_some_and(|collapse_key| collapse_key.is_empty() || collapse_key.len() > Self::MAX_COLLAPSE_KEY_LENGTH) {
+            return Err(Error::ValidationError(
+                format!("collapse_key must contain between 1 and {} bytes", Self::MAX_COLLAPSE_KEY_LENGTH)
+            ));
+        }
+
+        if let Some(image) = &self.image {
+            if !image.starts_with("https://") && !image.starts_with("http://") {
+                return Err(Error::ValidationError("image must be an http(s) url".to_string()));
+            }
+        }
+
+        if self.dedup_key.as_ref().is_some_and(|dedup_key| dedup_key.is_empty() || dedup_key.len() > Self::MAX_DEDUP_KEY_LENGTH) {
+            return Err(Error::ValidationError(
+                format!("dedup_key must contain between 1 and {} bytes", Self::MAX_DEDUP_KEY_LENGTH)
+            ));
+        }
+
+        if self.dedup_window.is_some_and(|dedup_window| !(1..=Self::MAX_DEDUP_WINDOW).contains(&dedup_window)) {
+            return Err(Error::ValidationError(format!("dedup_window must be between 1 and {} seconds", Self::MAX_DEDUP_WINDOW)));
+        }
+
+        if self.badge.is_some_and(|badge| badge < 0) {
+            return Err(Error::ValidationError("badge must be positive".to_string()));
+        }
+
+        let color = self.android.as_ref().and_then(|android| android.color.as_ref());
+        if let Some(color) = color {
+            let is_valid = color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+            if !is_valid {
+                return Err(Error::ValidationError("android color must use the #RRGGBB format".to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Fields set on the overrides win, the others are kept from the template.
+    pub fn merge(&self, overrides: &NotificationOptions) -> NotificationOptions {
+        NotificationOptions {
+            silent: overrides.silent.or(self.silent),
+            priority: overrides.priority.clone().or(self.priority.clone()),
+            ttl: overrides.ttl.or(self.ttl),
+            collapse_key: overrides.collapse_key.clone().or(self.collapse_key.clone()),
+            image: overrides.image.clone().or(self.image.clone()),
+            sound: overrides.sound.clone().or(self.sound.clone()),
+            badge: overrides.badge.or(self.badge),
+            click_action: overrides.click_action.clone().or(self.click_action.clone()),
+            dedup_key: overrides.dedup_key.clone().or(self.dedup_key.clone()),
+            deduplicate: overrides.deduplicate.or(self.deduplicate),
+            dedup_window: overrides.dedup_window.or(self.dedup_window),
+            android: match (&self.android, &overrides.android) {
+                (Some(android), Some(overrides)) => Some(AndroidNotificationOptions {
+                    channel_id: overrides.channel_id.clone().or(android.channel_id.clone()),
+                    icon: overrides.icon.clone().or(android.icon.clone()),
+                    color: overrides.color.clone().or(android.color.clone())
+                }),
+                (android, overrides) => overrides.clone().or(android.clone())
+            },
+            ios: match (&self.ios, &overrides.ios) {
+                (Some(ios), Some(overrides)) => Some(IosNotificationOptions {
+                    category: overrides.category.clone().or(ios.category.clone()),
+                    thread_id: overrides.thread_id.clone().or(ios.thread_id.clone())
+                }),
+                (ios, overrides) => overrides.clone().or(ios.clone())
+            }
+        }
+    }
+
+    pub fn is_silent(&self) -> bool {
+        self.silent.unwrap_or(false)
+    }
+
+    // Visible notifications default to a high priority, silent ones to a normal priority.
+    pub fn is_high_priority(&self) -> bool {
+        match &self.priority {
+            Some(priority) => priority == Self::HIGH_PRIORITY,
+            None => !self.is_silent()
+        }
+    }
+
+    pub fn is_deduplicated(&self) -> bool {
+        match self.deduplicate {
+            Some(deduplicate) => deduplicate,
+            None => self.dedup_key.is_some()
+        }
+    }
+
+    pub fn get_dedup_window(&self) -> i64 {
+        self.dedup_window.unwrap_or(Self::DEFAULT_DEDUP_WINDOW)
+    }
+
+    pub fn get_android_options(&self) -> AndroidNotificationOptions {
+        self.android.clone().unwrap_or_default()
+    }
+
+    pub fn get_ios_options(&self) -> IosNotificationOptions {
+        self.ios.clone().unwrap_or_default()
+    }
+}
\ No newline at end of file
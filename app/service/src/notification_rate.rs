@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use core::error::Error;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct NotificationRate {
+    pub messages: i32,
+    pub per: String
+}
+
+// Notifications sharing a key share the same token bucket, whichever consumer sends them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Throttle {
+    pub key: String,
+    pub rate: NotificationRate
+}
+
+impl NotificationRate {
+    pub const SECOND: &'static str = "second";
+    pub const MINUTE: &'static str = "minute";
+    // The consumer sleeps this long between two reads of the stream.
+    pub const CONSUMER_POLL_INTERVAL_MILLISECONDS: i64 = 5_000;
+    const MAX_MESSAGES: i32 = 100_000;
+
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.per != Self::SECOND && self.per != Self::MINUTE {
+            return Err(Error::ValidationError("rate per must be second or minute".to_string()));
+        }
+
+        if !(1..=Self::MAX_MESSAGES).contains(&self.messages) {
+            return Err(Error::ValidationError(format!("rate messages must be between 1 and {}", Self::MAX_MESSAGES)));
+        }
+
+        Ok(())
+    }
+
+    // Reads a `<messages>/<second|minute>` rate, as given in the settings.
+    pub fn parse(value: &str) -> Result<Self, Error> {
+        let rate = match value.trim().split_once('/') {
+            Some((messages, per)) => match messages.trim().parse::<i32>() {
+                Ok(messages) => NotificationRate { messages, per: per.trim().to_string() },
+                Err(_) => return Err(Error::ValidationError(format!("rate {} messages must be integer", value)))
+            },
+            None => return Err(Error::ValidationError(format!("rate {} must be <messages>/<second|minute>", value)))
+        };
+        rate.validate()?;
+
+        Ok(rate)
+    }
+
+    pub fn get_period_in_milliseconds(&self) -> i64 {
+        if self.per == Self::MINUTE { 60_000 } else { 1_000 }
+    }
+
+    // The bucket holds one second worth of messages, so a per minute rate is spread over the minute.
+    pub fn get_capacity(&self) -> i64 {
+        let per_second = (self.messages as i64 * 1_000 + self.get_period_in_milliseconds() - 1) / self.get_period_in_milliseconds();
+
+        per_second.max(1)
+    }
+
+    // Throttled notifications are only retried at the next poll, a smaller bucket would cap the rate below the given one.
+    pub fn get_throttle_capacity(&self) -> i64 {
+        self.get_messages_over(Self::CONSUMER_POLL_INTERVAL_MILLISECONDS).max(self.get_capacity())
+    }
+
+    pub fn get_messages_over(&self, milliseconds: i64) -> i64 {
+        let messages = (self.messages as i64 * milliseconds + self.get_period_in_milliseconds() - 1) / self.get_period_in_milliseconds();
+
+        messages.max(1)
+    }
+
+    pub fn get_tokens_per_millisecond(&self) -> f64 {
+        self.messages as f64 / self.get_period_in_milliseconds() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(messages: i32, per: &str) -> NotificationRate {
+        NotificationRate { messages, per: per.to_string() }
+    }
+
+    #[test]
+    fn get_capacity_holds_one_second_of_messages() {
+        assert_eq!(rate(1, NotificationRate::SECOND).get_capacity(), 1);
+        assert_eq!(rate(500, NotificationRate::SECOND).get_capacity(), 500);
+        assert_eq!(rate(600, NotificationRate::MINUTE).get_capacity(), 10);
+        // Rounded up, and never empty for slow rates.
+        assert_eq!(rate(90, NotificationRate::MINUTE).get_capacity(), 2);
+        assert_eq!(rate(1, NotificationRate::MINUTE).get_capacity(), 1);
+    }
+
+    #[test]
+    fn get_throttle_capacity_holds_a_poll_interval_of_messages() {
+        assert_eq!(rate(500, NotificationRate::SECOND).get_throttle_capacity(), 2_500);
+        assert_eq!(rate(600, NotificationRate::MINUTE).get_throttle_capacity(), 50);
+        assert_eq!(rate(1, NotificationRate::MINUTE).get_throttle_capacity(), 1);
+    }
+
+    #[test]
+    fn get_messages_over_scales_the_rate_to_the_duration() {
+        assert_eq!(rate(10, NotificationRate::SECOND).get_messages_over(30_000), 300);
+        assert_eq!(rate(90, NotificationRate::MINUTE).get_messages_over(30_000), 45);
+        assert_eq!(rate(1, NotificationRate::MINUTE).get_messages_over(1_000), 1);
+    }
+
+    #[test]
+    fn validate_rejects_unknown_periods_and_out_of_range_messages() {
+        assert!(rate(10, "hour").validate().is_err());
+        assert!(rate(0, NotificationRate::SECOND).validate().is_err());
+        assert!(rate(100_001, NotificationRate::SECOND).validate().is_err());
+        assert!(rate(100_000, NotificationRate::MINUTE).validate().is_ok());
+    }
+
+    #[test]
+    fn parse_reads_settings_values() {
+        assert_eq!(NotificationRate::parse("100/second").unwrap(), rate(100, NotificationRate::SECOND));
+        assert_eq!(NotificationRate::parse(" 30 / minute ").unwrap(), rate(30, NotificationRate::MINUTE));
+    }
+
+    #[test]
+    fn parse_rejects_malformed_settings_values() {
+        for value in ["", "100", "many/second", "100/hour", "0/second", "-5/minute"] {
+            assert!(matches!(NotificationRate::parse(value), Err(Error::ValidationError(_))), "{} should be rejected", value);
+        }
+    }
+}
\ No newline at end of file
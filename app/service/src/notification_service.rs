@@ -1,16 +1,20 @@
-use std::{
-    collections::HashMap,
-    fs
-};
+use std::collections::HashMap;
 use types::{
+    extra_data::ExtraData,
     string::FirebaseToken,
     enums::DeviceType,
+    locale::Locale,
 };
-use crate::rows::notification_row::NotificationRow;
-use core::{
-    error::Error,
-    config::Config,
+use crate::{
+    app_service::AppService,
+    notification_options::NotificationOptions,
+    notification_template_service::{DigestInformation, NotificationInformation, NotificationTemplateService},
+    rows::{
+        notification_row::NotificationRow,
+        user_from_row::UserFromRow
+    }
 };
+use core::error::Error;
 use fcm::{
     FcmClient,
     message::{
@@ -19,151 +23,236 @@ use fcm::{
         AndroidMessagePriority,
         AndroidNotification,
         ApnsConfig,
+        ApnsFcmOptions,
         WebpushConfig,
         Message,
         Target
     },
 };
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde_json::{json, Map, Value};
 
 pub struct NotificationService {
-    firebase_client: FcmClient,
+    // Clients by credentials path, apps sharing a Firebase project share their client.
+    firebase_clients: HashMap<String, FcmClient>,
+    app_service: AppService,
+    notification_template_service: NotificationTemplateService,
 }
 
 impl NotificationService {
-    pub async fn new() -> Self {
+    pub fn new() -> Self {
         NotificationService {
-            firebase_client: FcmClient::builder()
-                .service_account_key_json_path(Config::get_google_service_account_credentials_path())
-                .build()
-                .await
-                .expect("Bad Google credentials given.")
+            firebase_clients: HashMap::new(),
+            app_service: AppService::new(),
+            notification_template_service: NotificationTemplateService::new()
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct NotificationInformation {
-    pub title: String,
-    pub body: String,
-    pub params: Option<Vec<String>>
-}
+impl NotificationService {
+    const FCM_MAX_PAYLOAD_SIZE: usize = 4096;
+    const APNS_MAX_PAYLOAD_SIZE: usize = 4096;
+    const PROVIDER_PAYLOAD_TOO_LARGE_ERRORS: [&'static str; 3] = ["PayloadTooLarge", "too big", "too large"];
 
-impl NotificationInformation {
-    pub fn parse_with_template_data(&mut self, extra_data: &Option<HashMap<String, String>>) -> &Self {
-        if extra_data.is_none() {
-            return self;
+    // Resolved once per notification, the consumer hands it to every step below.
+    pub async fn get_notification_information(&mut self, notification_row: &NotificationRow) -> Result<Option<NotificationInformation>, Error> {
+        self.notification_template_service.get_notification_information_by_key(
+            notification_row.app_id,
+            notification_row.notification_type.as_str()
+        ).await
+    }
+
+    pub async fn send_notification(
+        &mut self,
+        device_token: &FirebaseToken,
+        device_type: &DeviceType,
+        locale: &Option<Locale>,
+        notification_row: &NotificationRow,
+        notification_information: &NotificationInformation
+    ) -> Result<NotificationInformation, Error> {
+        let template_data = if notification_row.template_data.is_none() { None } else { Some(serde_json::from_str::<HashMap<String, String>>(&notification_row.clone().template_data.unwrap()).unwrap()) };
+
+        let mut information = match &notification_row.digest {
+            Some(digest) => notification_information.get_rendered_digest(locale, device_type, digest, &notification_row.options)?,
+            None => notification_information.get_rendered(locale, device_type, &template_data, &notification_row.options)?
+        };
+        // Only the time left since the notification was queued is given to the providers.
+        if let Some(expiration_date) = notification_row.get_expiration_date(information.options.ttl) {
+            information.options.ttl = Some((expiration_date - Utc::now()).num_seconds().max(0));
         }
+        let message = Self::get_message(device_token, device_type, &information, &notification_row.extra_data);
+        Self::check_payload_size(device_type, &message)?;
+        let response = self.get_firebase_client(notification_row.app_id).await?.send(message).await;
 
-        for (key, value) in extra_data.clone().unwrap() {
-            let pattern = format!("%{}%", key);
-            self.title = self.title.replace(pattern.clone().as_str(), value.as_str());
-            self.body = self.body.replace(pattern.clone().as_str(), value.as_str());
+        match response {
+            Ok(_) => Ok(information),
+            Err(err) if Self::PROVIDER_PAYLOAD_TOO_LARGE_ERRORS.iter().any(|error| err.to_string().contains(error)) => {
+                Err(Error::PayloadTooLargeError(err.to_string()))
+            },
+            Err(err) => Err(Error::ProviderError(err.to_string()))
         }
+    }
 
-        self
+    pub fn check_payload_size(device_type: &DeviceType, message: &Message) -> Result<(), Error> {
+        let size = Self::get_payload_size(device_type, message);
+        let max_size = if device_type.to_string() == DeviceType::IOS.to_string() {
+            Self::APNS_MAX_PAYLOAD_SIZE
+        } else {
+            Self::FCM_MAX_PAYLOAD_SIZE
+        };
+
+        if size > max_size {
+            return Err(Error::PayloadTooLargeError(
+                format!("{} payload of {} bytes exceeds the {} bytes limit", device_type, size, max_size)
+            ));
+        }
+
+        Ok(())
     }
-}
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct NotificationsRule<T = HashMap<String, NotificationInformation>>(pub T);
+    // Measured as each device receives it, every field counted once.
+    // iOS devices receive the APNs payload, in which FCM adds the alert and merges the data.
+    // Android devices receive the data and notification, which the AndroidConfig overrides.
+    pub fn get_payload_size(device_type: &DeviceType, message: &Message) -> usize {
+        let payload = if device_type.to_string() == DeviceType::IOS.to_string() {
+            let mut payload = match message.apns.as_ref().and_then(|apns| apns.payload.clone()) {
+                Some(Value::Object(payload)) => payload,
+                _ => Map::new()
+            };
+            if let Some(notification) = &message.notification {
+                let mut aps = match payload.remove(ExtraData::APS_KEY) {
+                    Some(Value::Object(aps)) => aps,
+                    _ => Map::new()
+                };
+                aps.insert("alert".to_string(), json!({ "title": notification.title, "body": notification.body }));
+                payload.insert(ExtraData::APS_KEY.to_string(), Value::Object(aps));
+            }
+            if let Some(Value::Object(data)) = &message.data {
+                for (key, value) in data {
+                    payload.entry(key.clone()).or_insert(value.clone());
+                }
+            }
 
-impl NotificationsRule<HashMap<String, NotificationInformation>> {
-    pub fn get_by_key(&mut self, key: &str) -> Option<&NotificationInformation> {
-        self.0.get(key)
+            Value::Object(payload)
+        } else {
+            let mut payload = match message.android.as_ref().map(serde_json::to_value) {
+                Some(Ok(Value::Object(payload))) => payload,
+                _ => Map::new()
+            };
+            if payload.get("data").filter(|value| !value.is_null()).is_none() {
+                payload.insert("data".to_string(), message.data.clone().unwrap_or_default());
+            }
+            if payload.get("notification").filter(|value| !value.is_null()).is_none() {
+                payload.insert("notification".to_string(), serde_json::to_value(&message.notification).unwrap_or_default());
+            }
+
+            Value::Object(payload)
+        };
+
+        serde_json::to_string(&Self::without_nulls(payload)).map(|json| json.len()).unwrap_or_default()
     }
-}
 
-impl NotificationService {
-    pub async fn send_notification(
-        &mut self,
-        device_token: &FirebaseToken,
-        device_type: &DeviceType,
+    // Silent notifications are never seen by the user, so quiet hours do not apply to them.
+    pub fn get_quiet_hours_end(
+        user: &UserFromRow,
         notification_row: &NotificationRow,
-    ) -> Result<(), Error> {
-        let message = self.get_message(device_token, device_type, notification_row)?;
-        let response = self.firebase_client.send(message).await;
+        information: &NotificationInformation
+    ) -> Option<DateTime<Utc>> {
+        if !information.respect_quiet_hours || information.options.merge(&notification_row.options).is_silent() {
+            return None;
+        }
 
-        match response {
-            Ok(_) => Ok(()),
-            Err(err) => Err(Error::ProviderError(err.to_string()))
+        user.get_quiet_hours_end(Utc::now())
+    }
+
+    // The digest settings of the notification type, rows already flushed from a buffer have none.
+    pub fn get_digest(notification_row: &NotificationRow, information: &NotificationInformation) -> Option<DigestInformation> {
+        if notification_row.digest.is_some() {
+            return None;
         }
+
+        information.digest.clone()
     }
-}
 
-impl NotificationService {
-    fn get_message(
-        &mut self,
+    pub fn is_expired(notification_row: &NotificationRow, information: &NotificationInformation) -> bool {
+        let options = information.options.merge(&notification_row.options);
+
+        // A zero ttl is left to the providers, which try a single immediate delivery.
+        match notification_row.get_expiration_date(options.ttl.filter(|ttl| *ttl > 0)) {
+            Some(expiration_date) => expiration_date <= Utc::now(),
+            None => false
+        }
+    }
+
+    // The dedup key of the notification with its window in seconds, when it is deduplicated.
+    pub fn get_dedup_key(notification_row: &NotificationRow, information: &NotificationInformation) -> Option<(String, i64)> {
+        let options = information.options.merge(&notification_row.options);
+
+        notification_row.get_dedup_key(&options).map(|dedup_key| (dedup_key, options.get_dedup_window()))
+    }
+
+    pub fn get_message(
         device_token: &FirebaseToken,
         device_type: &DeviceType,
-        notification_row: &NotificationRow,
-    ) -> Result<Message, Error> {
-
-        let notification_information: Option<NotificationInformation> = self.get_notification_information_by_key(
-            notification_row.notification_type.as_str()
-        )?;
-
-        if notification_information.is_none() {
-            return Err(
-                Error::MissingDataError(
-                    format!(
-                        "Missing key for notifications: {}",
-                        notification_row.notification_type.as_str()
-                    )
-                )
-            )
-        }
-
-        let extra_data = if notification_row.extra_data.is_none() { None } else {
-            let hm: HashMap<String, String> = serde_json::from_str(&notification_row.clone().extra_data.unwrap()).unwrap();
-            Some(serde_json::to_value::<HashMap<String, String>>(hm).unwrap())
-        };
-        let template_data = if notification_row.template_data.is_none() { None } else { Some(serde_json::from_str::<HashMap<String, String>>(&notification_row.clone().template_data.unwrap()).unwrap()) };
+        information: &NotificationInformation,
+        extra_data: &Option<ExtraData>,
+    ) -> Message {
+        let options = &information.options;
+        let payload = Self::get_apns_payload(extra_data, options);
+        let extra_data = extra_data.as_ref().map(|extra_data| Value::Object(extra_data.get_flattened()));
+        if options.is_silent() {
+            return Self::get_silent_message(device_token, device_type, options, extra_data, payload);
+        }
 
-        let info = notification_information.unwrap().parse_with_template_data(&template_data).clone();
         let notification = Some(Notification {
-            title: Some(info.clone().title),
-            body: Some(info.clone().body),
-            ..Default::default()
+            title: Some(information.title.clone()),
+            body: Some(information.body.clone()),
+            image: options.image.clone(),
         });
 
         if device_type.to_string() == DeviceType::Android.to_string() {
-            return Ok(Message {
+            return Message {
                 data: extra_data.clone(),
                 notification,
-                android: Some(AndroidConfig {
-                    data: extra_data.clone(),
-                    priority: Some(AndroidMessagePriority::High),
-                    notification: Some(AndroidNotification {
-                        title: Some(info.clone().title),
-                        body: Some(info.clone().body),
+                android: Some(Self::get_android_config(options, extra_data.clone(), Some(AndroidNotification {
+                        title: Some(information.title.clone()),
+                        body: Some(information.body.clone()),
+                        image: options.image.clone(),
+                        sound: options.sound.clone(),
+                        notification_count: options.badge,
+                        click_action: options.click_action.clone(),
+                        channel_id: options.get_android_options().channel_id,
+                        icon: options.get_android_options().icon,
+                        color: options.get_android_options().color,
                         ..Default::default()
-                    }),
-                    ..Default::default()
-                }),
+                    }))),
                 webpush: None,
                 apns: None,
                 fcm_options: None,
                 target: Target::Token(device_token.into()),
-            });
+            };
         }
         
         if device_type.to_string() == DeviceType::IOS.to_string() {
-            return Ok(Message {
+            return Message {
                 data: extra_data.clone(),
                 notification,
                 android: None,
                 webpush: None,
                 apns: Some(ApnsConfig {
-                    payload: extra_data.clone(),
-                    ..Default::default()
+                    headers: Self::get_apns_headers(options),
+                    payload,
+                    fcm_options: options.image.clone().map(|image| ApnsFcmOptions {
+                        image: Some(image),
+                        ..Default::default()
+                    }),
                 }),
                 fcm_options: None,
                 target: Target::Token(device_token.into()),
-            });
+            };
         }
 
-        Ok(Message {
+        Message {
             data: None,
             notification,
             android: None,
@@ -174,14 +263,215 @@ impl NotificationService {
             apns: None,
             fcm_options: None,
             target: Target::Token(device_token.into()),
-        })
+        }
+    }
+}
+
+impl NotificationService {
+    // The app is read on every send, so updated credentials are picked up without restarting the consumer.
+    async fn get_firebase_client(&mut self, app_id: i32) -> Result<&FcmClient, Error> {
+        let app = self.app_service.find_app_by_id(app_id.into()).await?;
+        let credentials_path = app.get_google_service_account_credentials_path();
+
+        if !self.firebase_clients.contains_key(&credentials_path) {
+            let firebase_client = match FcmClient::builder()
+                .service_account_key_json_path(credentials_path.clone())
+                .build()
+                .await {
+                Ok(firebase_client) => firebase_client,
+                Err(err) => return Err(Error::ConfigError(format!("Bad Google credentials given for app {}: {}", app.name, err)))
+            };
+            self.firebase_clients.insert(credentials_path.clone(), firebase_client);
+        }
+
+        Ok(&self.firebase_clients[&credentials_path])
+    }
+
+    fn get_silent_message(
+        device_token: &FirebaseToken,
+        device_type: &DeviceType,
+        options: &NotificationOptions,
+        extra_data: Option<Value>,
+        payload: Option<Value>,
+    ) -> Message {
+        if device_type.to_string() == DeviceType::Android.to_string() {
+            return Message {
+                data: extra_data.clone(),
+                notification: None,
+                android: Some(Self::get_android_config(options, extra_data.clone(), None)),
+                webpush: None,
+                apns: None,
+                fcm_options: None,
+                target: Target::Token(device_token.into()),
+            };
+        }
+
+        if device_type.to_string() == DeviceType::IOS.to_string() {
+            return Message {
+                data: extra_data.clone(),
+                notification: None,
+                android: None,
+                webpush: None,
+                apns: Some(ApnsConfig {
+                    headers: Self::get_apns_headers(options),
+                    payload,
+                    ..Default::default()
+                }),
+                fcm_options: None,
+                target: Target::Token(device_token.into()),
+            };
+        }
+
+        Message {
+            data: extra_data.clone(),
+            notification: None,
+            android: None,
+            webpush: None,
+            apns: None,
+            fcm_options: None,
+            target: Target::Token(device_token.into()),
+        }
+    }
+
+    fn without_nulls(value: Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .filter(|(_, value)| !value.is_null())
+                    .map(|(key, value)| (key, Self::without_nulls(value)))
+                    .collect()
+            ),
+            Value::Array(values) => Value::Array(values.into_iter().map(Self::without_nulls).collect()),
+            value => value
+        }
+    }
+
+    fn get_android_config(
+        options: &NotificationOptions,
+        extra_data: Option<Value>,
+        notification: Option<AndroidNotification>,
+    ) -> AndroidConfig {
+        AndroidConfig {
+            data: extra_data,
+            priority: Some(if options.is_high_priority() { AndroidMessagePriority::High } else { AndroidMessagePriority::Normal }),
+            ttl: options.ttl.map(|ttl| format!("{}s", ttl)),
+            collapse_key: options.collapse_key.clone(),
+            notification,
+            ..Default::default()
+        }
+    }
+
+    fn get_apns_headers(options: &NotificationOptions) -> Option<Value> {
+        let mut headers: Map<String, Value> = Map::new();
+        // Apple requires the background push type and a low priority for content-available only pushes.
+        if options.is_silent() {
+            headers.insert("apns-push-type".to_string(), Value::from("background"));
+            headers.insert("apns-priority".to_string(), Value::from("5"));
+        } else if options.priority.is_some() {
+            headers.insert("apns-priority".to_string(), Value::from(if options.is_high_priority() { "10" } else { "5" }));
+        }
+        // A zero ttl keeps its APNs meaning : deliver now or never.
+        if let Some(ttl) = options.ttl {
+            let expiration = if ttl == 0 { 0 } else { Utc::now().timestamp() + ttl };
+            headers.insert("apns-expiration".to_string(), Value::from(expiration.to_string()));
+        }
+        if let Some(collapse_key) = &options.collapse_key {
+            headers.insert("apns-collapse-id".to_string(), Value::from(collapse_key.clone()));
+        }
+
+        if headers.is_empty() {
+            return None;
+        }
+
+        Some(Value::Object(headers))
+    }
+
+    fn get_apns_payload(extra_data: &Option<ExtraData>, options: &NotificationOptions) -> Option<Value> {
+        let mut payload: Map<String, Value> = extra_data.clone().unwrap_or_default().0;
+        // An aps dictionary given in extra_data is kept, the options are added to it.
+        let mut aps: Map<String, Value> = match payload.remove(ExtraData::APS_KEY) {
+            Some(Value::Object(aps)) => aps,
+            _ => Map::new()
+        };
+
+        if options.is_silent() {
+            aps.insert("content-available".to_string(), Value::from(1));
+            payload.insert(ExtraData::APS_KEY.to_string(), Value::Object(aps));
+            return Some(Value::Object(payload));
+        }
+
+        let ios = options.get_ios_options();
+        if let Some(sound) = &options.sound {
+            aps.insert("sound".to_string(), Value::from(sound.clone()));
+        }
+        if let Some(badge) = options.badge {
+            aps.insert("badge".to_string(), Value::from(badge));
+        }
+        // As FCM does for its legacy api, the click action is the APNs category when none is given.
+        if let Some(category) = ios.category.or(options.click_action.clone()) {
+            aps.insert("category".to_string(), Value::from(category));
+        }
+        if let Some(thread_id) = ios.thread_id {
+            aps.insert("thread-id".to_string(), Value::from(thread_id));
+        }
+        if options.image.is_some() {
+            aps.insert("mutable-content".to_string(), Value::from(1));
+        }
+        if !aps.is_empty() {
+            payload.insert(ExtraData::APS_KEY.to_string(), Value::Object(aps));
+        }
+
+        if payload.is_empty() {
+            return None;
+        }
+
+        Some(Value::Object(payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn information() -> NotificationInformation {
+        serde_json::from_value(json!({ "title": "Order shipped", "body": "b".repeat(1000) })).unwrap()
+    }
+
+    fn extra_data(value: &str) -> Option<ExtraData> {
+        serde_json::from_value(json!({ "order_id": value })).unwrap()
+    }
+
+    fn get_payload_size(device_type: DeviceType, extra_data: &Option<ExtraData>) -> usize {
+        let message = NotificationService::get_message(&"token".to_string().into(), &device_type, &information(), extra_data);
+
+        NotificationService::get_payload_size(&device_type, &message)
+    }
+
+    #[test]
+    fn get_payload_size_counts_the_android_fields_once() {
+        let size = get_payload_size(DeviceType::Android, &None);
+        let size_with_extra_data = get_payload_size(DeviceType::Android, &extra_data(&"x".repeat(1000)));
+
+        assert!(size > 1000 && size < 1200, "{}", size);
+        assert!(size_with_extra_data - size > 1000 && size_with_extra_data - size < 1100, "{}", size_with_extra_data - size);
+    }
+
+    #[test]
+    fn get_payload_size_counts_the_ios_fields_once() {
+        let size = get_payload_size(DeviceType::IOS, &None);
+        let size_with_extra_data = get_payload_size(DeviceType::IOS, &extra_data(&"x".repeat(1000)));
+
+        assert!(size > 1000 && size < 1200, "{}", size);
+        assert!(size_with_extra_data - size > 1000 && size_with_extra_data - size < 1100, "{}", size_with_extra_data - size);
     }
 
-    fn get_notification_information_by_key(&mut self, key: &str) -> Result<Option<NotificationInformation>, Error> {
-        let contents = fs::read_to_string(Config::get_notification_resources_path())
-            .expect("Could not read file");
+    #[test]
+    fn get_payload_size_merges_the_data_into_the_apns_payload() {
+        let mut message = NotificationService::get_message(&"token".to_string().into(), &DeviceType::IOS, &information(), &None);
+        let size = NotificationService::get_payload_size(&DeviceType::IOS, &message);
+        message.data = Some(json!({ "order_id": "x".repeat(1000) }));
 
-        let mut content: NotificationsRule = serde_yaml::from_str::<NotificationsRule>(&contents).unwrap();
-        Ok(content.get_by_key(key).cloned())
+        let size_with_data = NotificationService::get_payload_size(&DeviceType::IOS, &message);
+        assert!(size_with_data - size > 1000 && size_with_data - size < 1100, "{}", size_with_data - size);
     }
 }
\ No newline at end of file
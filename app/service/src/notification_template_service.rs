@@ -0,0 +1,842 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    str::FromStr,
+    sync::{Arc, OnceLock, RwLock}
+};
+use minijinja::Value;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use core::{
+    error::Error,
+    config::Config
+};
+use types::{
+    extra_data::ExtraData,
+    string::FirebaseToken,
+    enums::DeviceType,
+    identifier::Identifier,
+    locale::Locale,
+    user::ListOfUsersWithExtraData
+};
+use crate::{
+    app_service::AppService,
+    notification_catalog::NotificationCatalog,
+    notification_options::NotificationOptions,
+    notification_service::NotificationService,
+    redis_service::RedisService,
+    user_service::UserService,
+    template_renderer::TemplateRenderer,
+    rows::{
+        notification_row::NotificationDigest,
+        user_from_row::UserFromRow,
+        notification_template_from_row::{CountNotificationTemplateFromRow, NotificationTemplateFromRow}
+    }
+};
+
+pub type ListOfNotificationTemplateFromRow = Vec<NotificationTemplateFromRow>;
+// Database templates by app and key, with the template generation of the app they were read at.
+type TemplatesCache = RwLock<HashMap<(i32, String), (i64, ListOfNotificationTemplateFromRow)>>;
+
+static TEMPLATES: OnceLock<TemplatesCache> = OnceLock::new();
+
+pub struct NotificationTemplateService {
+    redis_service: RedisService
+}
+
+impl NotificationTemplateService {
+    pub fn new() -> Self {
+        NotificationTemplateService { redis_service: RedisService::new() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NotificationInformation {
+    pub title: String,
+    pub body: String,
+    pub params: Option<Vec<String>>,
+    pub optional_params: Option<Vec<String>>,
+    #[serde(default)]
+    pub respect_quiet_hours: bool,
+    // Users opt out of notifications by category.
+    #[serde(default)]
+    pub category: Option<String>,
+    // Notifications of the type are buffered per user and sent as a single digest.
+    #[serde(default)]
+    pub digest: Option<DigestInformation>,
+    #[serde(default)]
+    pub version: Option<i32>,
+    #[serde(skip)]
+    pub locale: Option<String>,
+    #[serde(default)]
+    pub options: NotificationOptions,
+    #[serde(default)]
+    pub platforms: HashMap<String, PlatformNotificationInformation>,
+    #[serde(default)]
+    pub locales: HashMap<String, LocalizedNotificationInformation>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LocalizedNotificationInformation {
+    pub title: String,
+    pub body: String,
+    #[serde(default)]
+    pub version: Option<i32>,
+    #[serde(default)]
+    pub digest: Option<DigestInformation>,
+    #[serde(default)]
+    pub platforms: HashMap<String, PlatformNotificationInformation>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PlatformNotificationInformation {
+    pub title: Option<String>,
+    pub body: Option<String>
+}
+
+// The window and max count belong to the notification type, localized digests only bring their title and body.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DigestInformation {
+    pub title: String,
+    pub body: String,
+    #[serde(default)]
+    pub window: Option<i64>,
+    #[serde(default)]
+    pub max_count: Option<i64>
+}
+
+pub struct NotificationTemplate {
+    pub title: String,
+    pub body: String,
+    pub params: Option<Vec<String>>,
+    pub optional_params: Option<Vec<String>>,
+    pub respect_quiet_hours: bool,
+    pub category: Option<String>,
+    pub digest: Option<DigestInformation>,
+    pub options: NotificationOptions,
+    pub platform_overrides: HashMap<String, PlatformNotificationInformation>
+}
+
+impl DigestInformation {
+    const DEFAULT_WINDOW: i64 = 600;
+    const MAX_WINDOW: i64 = 86_400;
+    const MAX_COUNT: i64 = 100;
+
+    pub fn get_window(&self) -> i64 {
+        self.window.unwrap_or(Self::DEFAULT_WINDOW)
+    }
+
+    // Buffers are flushed at the latest once they hold the maximum count, keeping digests to a bounded size.
+    pub fn get_max_count(&self) -> i64 {
+        self.max_count.unwrap_or(Self::MAX_COUNT)
+    }
+
+    pub fn validate(&self, is_localized: bool) -> Result<(), Error> {
+        if self.title.trim().is_empty() || self.body.trim().is_empty() {
+            return Err(Error::ValidationError("digest title and body cannot be empty".to_string()));
+        }
+        if is_localized && (self.window.is_some() || self.max_count.is_some()) {
+            return Err(Error::ValidationError("digest window and max_count can only be set on the default locale".to_string()));
+        }
+        if self.window.is_some_and(|window| !(1..=Self::MAX_WINDOW).contains(&window)) {
+            return Err(Error::ValidationError(format!("digest window must be between 1 and {} seconds", Self::MAX_WINDOW)));
+        }
+        if self.max_count.is_some_and(|max_count| !(2..=Self::MAX_COUNT).contains(&max_count)) {
+            return Err(Error::ValidationError(format!("digest max_count must be between 2 and {}", Self::MAX_COUNT)));
+        }
+
+        for template in [&self.title, &self.body] {
+            TemplateRenderer::validate(template)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl NotificationInformation {
+    pub const MAX_CATEGORY_LENGTH: usize = 100;
+
+    pub fn localize(&self, locale: &Option<Locale>) -> NotificationInformation {
+        let mut information = self.clone();
+        let Some(locale) = locale else {
+            return information;
+        };
+
+        for candidate in locale.get_fallback_chain() {
+            let localized = self.locales.iter()
+                .find(|(key, _)| Locale::normalize(key) == candidate);
+
+            if let Some((key, localized)) = localized {
+                information.title = localized.title.clone();
+                information.body = localized.body.clone();
+                information.version = localized.version;
+                information.locale = Some(Locale::normalize(key));
+                information.platforms = localized.platforms.clone();
+                if let (Some(digest), Some(localized_digest)) = (&mut information.digest, &localized.digest) {
+                    digest.title = localized_digest.title.clone();
+                    digest.body = localized_digest.body.clone();
+                }
+                break;
+            }
+        }
+
+        information
+    }
+
+    pub fn for_device_type(&self, device_type: &DeviceType) -> NotificationInformation {
+        let mut information = self.clone();
+        let platform = self.platforms.iter()
+            .find(|(key, _)| DeviceType::from_str(key).is_ok_and(|key| key == *device_type))
+            .map(|(_, platform)| platform);
+
+        if let Some(platform) = platform {
+            information.title = platform.title.clone().unwrap_or(information.title);
+            information.body = platform.body.clone().unwrap_or(information.body);
+        }
+
+        information
+    }
+
+    pub fn render(&self, template_data: &Option<HashMap<String, String>>) -> Result<NotificationInformation, Error> {
+        let context = Value::from_serialize(template_data.clone().unwrap_or_default());
+
+        let mut information = self.clone();
+        information.title = TemplateRenderer::render(&self.title, &context)?;
+        information.body = TemplateRenderer::render(&self.body, &context)?;
+
+        Ok(information)
+    }
+
+    pub fn get_rendered(
+        &self,
+        locale: &Option<Locale>,
+        device_type: &DeviceType,
+        template_data: &Option<HashMap<String, String>>,
+        options: &NotificationOptions
+    ) -> Result<NotificationInformation, Error> {
+        let mut information = self.localize(locale).for_device_type(device_type).render(template_data)?;
+        information.options = information.options.merge(options);
+
+        Ok(information)
+    }
+
+    pub fn get_rendered_digest(
+        &self,
+        locale: &Option<Locale>,
+        device_type: &DeviceType,
+        digest: &NotificationDigest,
+        options: &NotificationOptions
+    ) -> Result<NotificationInformation, Error> {
+        let mut information = self.localize(locale).for_device_type(device_type).render_digest(digest)?;
+        information.options = information.options.merge(options);
+
+        Ok(information)
+    }
+
+    // Digests of a single notification are sent with the template of the type, as is every digest of a type which lost its digest.
+    pub fn render_digest(&self, digest: &NotificationDigest) -> Result<NotificationInformation, Error> {
+        let (Some(digest_information), true) = (&self.digest, digest.count > 1) else {
+            return self.render(&digest.template_data.last().cloned());
+        };
+        let context = Value::from_serialize(serde_json::json!({
+            "count": digest.count,
+            "others": digest.count - 1,
+            "first": digest.template_data.first(),
+            "last": digest.template_data.last(),
+            "events": digest.template_data
+        }));
+
+        let mut information = self.clone();
+        information.title = TemplateRenderer::render(&digest_information.title, &context)?;
+        information.body = TemplateRenderer::render(&digest_information.body, &context)?;
+
+        Ok(information)
+    }
+
+    pub fn validate(&self, key: &str) -> Result<(), Error> {
+        let params = self.params.clone().unwrap_or_default();
+        let optional_params = self.optional_params.clone().unwrap_or_default();
+        if let Some(param) = params.iter().find(|param| optional_params.contains(param)) {
+            return Err(Error::ValidationError(format!("{}: {} cannot be both required and optional", key, param)));
+        }
+        if let Err(err) = self.options.validate() {
+            return Err(Error::ValidationError(format!("{}: {}", key, String::from(err))));
+        }
+        if let Some(category) = &self.category {
+            if category.trim().is_empty() || category.len() > Self::MAX_CATEGORY_LENGTH {
+                return Err(Error::ValidationError(format!("{}: category must be 1 to {} characters long", key, Self::MAX_CATEGORY_LENGTH)));
+            }
+        }
+        let digests = self.digest.iter().map(|digest| (digest, false))
+            .chain(self.locales.values().filter_map(|localized| localized.digest.as_ref()).map(|digest| (digest, true)));
+        for (digest, is_localized) in digests {
+            if let Err(err) = digest.validate(is_localized) {
+                return Err(Error::ValidationError(format!("{}: {}", key, String::from(err))));
+            }
+        }
+        if self.digest.is_none() && self.locales.values().any(|localized| localized.digest.is_some()) {
+            return Err(Error::ValidationError(format!("{}: localized digests need a default locale digest", key)));
+        }
+
+        let mut templates = Vec::from([&self.title, &self.body]);
+        let mut platforms = Vec::from([&self.platforms]);
+        for localized in self.locales.values() {
+            templates.extend([&localized.title, &localized.body]);
+            platforms.push(&localized.platforms);
+        }
+        for (platform_key, platform) in platforms.into_iter().flatten() {
+            if DeviceType::from_str(platform_key).is_err() {
+                return Err(Error::ValidationError(format!("{}: unknown platform {}", key, platform_key)));
+            }
+            templates.extend(platform.title.iter().chain(platform.body.iter()));
+        }
+
+        for template in templates {
+            if let Err(err) = TemplateRenderer::validate(template) {
+                return Err(Error::ValidationError(format!("{}: {}", key, String::from(err))));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get_template_data_violations(&self, template_data: &Option<HashMap<String, String>>) -> Option<String> {
+        let params = self.params.clone().unwrap_or_default();
+        let optional_params = self.optional_params.clone().unwrap_or_default();
+        let template_data = template_data.clone().unwrap_or_default();
+
+        let mut missing_params: Vec<&String> = params.iter()
+            .filter(|param| !template_data.contains_key(*param))
+            .collect();
+        let mut unknown_params: Vec<&String> = template_data.keys()
+            .filter(|key| !params.contains(key) && !optional_params.contains(key))
+            .collect();
+        missing_params.sort();
+        unknown_params.sort();
+
+        let mut violations: Vec<String> = Vec::from([]);
+        if !missing_params.is_empty() {
+            violations.push(format!("missing params [{}]", join(&missing_params)));
+        }
+        if !unknown_params.is_empty() {
+            violations.push(format!("unknown params [{}]", join(&unknown_params)));
+        }
+
+        if violations.is_empty() {
+            return None;
+        }
+
+        Some(violations.join(", "))
+    }
+
+    pub fn from_templates(templates: &[NotificationTemplateFromRow]) -> Option<NotificationInformation> {
+        let default_template = templates.iter().find(|template| template.is_default_locale())?;
+
+        Some(NotificationInformation {
+            title: default_template.title.clone(),
+            body: default_template.body.clone(),
+            params: default_template.params.as_ref().map(|params| params.0.clone()),
+            optional_params: default_template.optional_params.as_ref().map(|params| params.0.clone()),
+            respect_quiet_hours: default_template.respect_quiet_hours,
+            category: default_template.category.clone(),
+            digest: default_template.get_digest(),
+            version: Some(default_template.version),
+            locale: None,
+            options: default_template.get_options(),
+            platforms: default_template.get_platform_overrides(),
+            locales: templates.iter()
+                .filter(|template| !template.is_default_locale())
+                .map(|template| (
+                    template.locale.clone(),
+                    LocalizedNotificationInformation {
+                        title: template.title.clone(),
+                        body: template.body.clone(),
+                        version: Some(template.version),
+                        digest: template.get_digest(),
+                        platforms: template.get_platform_overrides()
+                    }
+                ))
+                .collect()
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NotificationsRule<T = HashMap<String, NotificationInformation>>(pub T);
+
+impl NotificationsRule<HashMap<String, NotificationInformation>> {
+    pub fn get_by_key(&self, key: &str) -> Option<&NotificationInformation> {
+        self.0.get(key)
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        for (key, information) in &self.0 {
+            information.validate(key)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl NotificationTemplateService {
+    pub async fn get_notification_information_by_key(&mut self, app_id: i32, key: &str) -> Result<Option<NotificationInformation>, Error> {
+        let templates = self.fetch_templates_by_key(app_id, key).await?;
+
+        // Keys without a default template in database keep using the notifications file of the app.
+        match NotificationInformation::from_templates(&templates) {
+            Some(information) => Ok(Some(information)),
+            None => Ok(self.get_catalog(app_id).await?.get_by_key(key).cloned())
+        }
+    }
+
+    pub async fn get_rendered_notification_information(
+        &mut self,
+        app_id: i32,
+        key: &str,
+        locale: &Option<Locale>,
+        device_type: &DeviceType,
+        template_data: &Option<HashMap<String, String>>,
+        options: &NotificationOptions
+    ) -> Result<NotificationInformation, Error> {
+        match self.get_notification_information_by_key(app_id, key).await? {
+            Some(information) => information.get_rendered(locale, device_type, template_data, options),
+            None => Err(Error::MissingDataError(format!("Missing key for notifications: {}", key)))
+        }
+    }
+
+    pub async fn validate_payload_size(
+        &mut self,
+        app_id: i32,
+        key: &str,
+        locale: &Option<Locale>,
+        device_type: &DeviceType,
+        template_data: &Option<HashMap<String, String>>,
+        extra_data: &Option<ExtraData>,
+        options: &NotificationOptions
+    ) -> Result<(), Error> {
+        let information = self.get_rendered_notification_information(app_id, key, locale, device_type, template_data, options).await?;
+        // The device token is not part of the measured payload, any value gives the same size.
+        let message = NotificationService::get_message(&FirebaseToken(String::new()), device_type, &information, extra_data);
+
+        NotificationService::check_payload_size(device_type, &message)
+    }
+
+    pub async fn validate_user_payload_size(
+        &mut self,
+        app_id: i32,
+        user_id: Identifier,
+        notification_type: &str,
+        template_data: &Option<HashMap<String, String>>,
+        extra_data: &Option<ExtraData>,
+        options: &NotificationOptions
+    ) -> Result<(), Error> {
+        let user = UserService::new().find_app_user_by_id(app_id, user_id).await?;
+
+        self.validate_payload_size(
+            app_id,
+            notification_type,
+            &user.locale.map(Locale),
+            &user.device_type.into(),
+            template_data,
+            extra_data,
+            options
+        ).await
+    }
+
+    // Missing users are reported first, every user is then read at once.
+    // Users sharing a platform, a locale and their template data share the rendered notification.
+    pub async fn validate_users_payload_size(
+        &mut self,
+        app_id: i32,
+        notification_type: &str,
+        users: &ListOfUsersWithExtraData
+    ) -> Result<(), Error> {
+        let user_ids: Vec<i32> = users.iter().map(|user| user.id).collect();
+        let user_rows: HashMap<i32, UserFromRow> = UserService::new().fetch_app_users_by_ids(app_id, &user_ids).await?
+            .into_iter()
+            .map(|user_row| (user_row.id, user_row))
+            .collect();
+        let information = self.get_notification_information_or_error(app_id, notification_type).await?;
+
+        let mut rendered: HashMap<(String, Option<String>, Option<String>), NotificationInformation> = HashMap::new();
+        for user in users {
+            let Some(user_row) = user_rows.get(&user.id) else {
+                continue;
+            };
+            let device_type: DeviceType = user_row.device_type.clone().into();
+            // Sorted, so the same template data always gives the same key.
+            let template_data = user.template_data.as_ref()
+                .map(|template_data| serde_json::to_string(&template_data.iter().collect::<BTreeMap<_, _>>()).unwrap());
+            let key = (user_row.device_type.clone(), user_row.locale.clone(), template_data);
+            if !rendered.contains_key(&key) {
+                let user_information = information.get_rendered(
+                    &user_row.locale.clone().map(Locale),
+                    &device_type,
+                    &user.template_data,
+                    &NotificationOptions::default()
+                )?;
+                rendered.insert(key.clone(), user_information);
+            }
+
+            // The device token is not part of the measured payload, any value gives the same size.
+            let message = NotificationService::get_message(&FirebaseToken(String::new()), &device_type, &rendered[&key], &user.extra_data);
+            if let Err(Error::PayloadTooLargeError(text)) = NotificationService::check_payload_size(&device_type, &message) {
+                return Err(Error::PayloadTooLargeError(format!("user {}: {}", user.id, text)));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn validate_template_data(
+        &mut self,
+        app_id: i32,
+        notification_type: &str,
+        template_data: &Option<HashMap<String, String>>
+    ) -> Result<(), Error> {
+        let information = self.get_notification_information_or_error(app_id, notification_type).await?;
+
+        match information.get_template_data_violations(template_data) {
+            Some(violations) => Err(Error::ValidationError(
+                format!("Invalid template_data for {}: {}", notification_type, violations)
+            )),
+            None => Ok(())
+        }
+    }
+
+    pub async fn validate_users_template_data(
+        &mut self,
+        app_id: i32,
+        notification_type: &str,
+        users: &ListOfUsersWithExtraData
+    ) -> Result<(), Error> {
+        let information = self.get_notification_information_or_error(app_id, notification_type).await?;
+
+        let violations: Vec<String> = users.iter()
+            .filter_map(|user| information.get_template_data_violations(&user.template_data)
+                .map(|violations| format!("user {}: {}", user.id, violations))
+            )
+            .collect();
+
+        if violations.is_empty() {
+            return Ok(());
+        }
+
+        Err(Error::ValidationError(
+            format!("Invalid template_data for {}: {}", notification_type, violations.join("; "))
+        ))
+    }
+
+    pub async fn create(
+        &mut self,
+        app_id: i32,
+        key: String,
+        locale: Option<String>,
+        template: NotificationTemplate
+    ) -> Result<NotificationTemplateFromRow, Error> {
+        let locale = locale.map(|locale| Locale::normalize(&locale)).unwrap_or_default();
+        Self::check_template(&key, &locale, &template)?;
+
+        let templates = self.fetch_templates_by_key(app_id, &key).await?;
+        if templates.iter().any(|existing| existing.locale == locale) {
+            return Err(Error::ValidationError(format!("Template {} already exists for this locale", key)));
+        }
+        if !locale.is_empty() && !templates.iter().any(|existing| existing.is_default_locale()) {
+            return Err(Error::ValidationError(format!("Template {} needs a default locale template first", key)));
+        }
+
+        let id = self.insert(app_id, &key, &locale, template).await?;
+
+        self.find_template_by_id(app_id, Identifier(id)).await
+    }
+
+    pub async fn find_template_by_id(&mut self, app_id: i32, template_id: Identifier) -> Result<NotificationTemplateFromRow, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_as::<_, NotificationTemplateFromRow>(
+            "SELECT * FROM `notification_templates` WHERE id = ? AND app_id = ?"
+        )
+            .bind::<i32>(template_id.into())
+            .bind::<i32>(app_id)
+            .fetch_optional(&conn)
+            .await?;
+
+        match result {
+            Some(template) => Ok(template),
+            None => Err(Error::NotFoundError(format!("Template with id {} does not exists", template_id.0)))
+        }
+    }
+
+    pub async fn fetch_template_paginated(&mut self, app_id: i32, offset: i32, limit: i32) -> Result<ListOfNotificationTemplateFromRow, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_as::<_, NotificationTemplateFromRow>(
+            r#"
+                SELECT * FROM `notification_templates`
+                WHERE app_id = ?
+                ORDER BY `key` ASC, `locale` ASC
+                LIMIT ?
+                OFFSET ?
+            "#
+        )
+            .bind::<i32>(app_id)
+            .bind::<i32>(limit)
+            .bind::<i32>(offset)
+            .fetch_all(&conn)
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn count(&mut self, app_id: i32) -> Result<i32, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_as::<_, CountNotificationTemplateFromRow>(
+            "SELECT COUNT(*) as total_count FROM `notification_templates` WHERE app_id = ?"
+        )
+            .bind::<i32>(app_id)
+            .fetch_one(&conn)
+            .await?;
+
+        Ok(result.total_count)
+    }
+
+    pub async fn update(
+        &mut self,
+        app_id: i32,
+        template_id: Identifier,
+        template: NotificationTemplate
+    ) -> Result<NotificationTemplateFromRow, Error> {
+        let existing = self.find_template_by_id(app_id, template_id).await?;
+        Self::check_template(&existing.key, &existing.locale, &template)?;
+
+        let conn = Config::get_database_conn().await?;
+        sqlx::query(
+            r#"
+                UPDATE `notification_templates`
+                SET title = ?, body = ?, params = ?, optional_params = ?, respect_quiet_hours = ?, category = ?, digest = ?,
+                    options = ?, platform_overrides = ?, version = version + 1, update_date = NOW()
+                WHERE id = ?
+            "#
+        )
+            .bind::<String>(template.title)
+            .bind::<String>(template.body)
+            .bind::<Option<Json<Vec<String>>>>(template.params.map(Json))
+            .bind::<Option<Json<Vec<String>>>>(template.optional_params.map(Json))
+            .bind::<bool>(template.respect_quiet_hours)
+            .bind::<Option<String>>(template.category)
+            .bind::<Option<Json<DigestInformation>>>(template.digest.map(Json))
+            .bind::<Json<NotificationOptions>>(Json(template.options))
+            .bind::<Json<HashMap<String, PlatformNotificationInformation>>>(Json(template.platform_overrides))
+            .bind::<i32>(template_id.into())
+            .execute(&conn)
+            .await?;
+        self.redis_service.increment_template_generation(app_id).await?;
+
+        self.find_template_by_id(app_id, template_id).await
+    }
+
+    pub async fn delete(&mut self, app_id: i32, template_id: Identifier) -> Result<(), Error> {
+        let template = self.find_template_by_id(app_id, template_id).await?;
+        if template.is_default_locale() && self.fetch_templates_by_key(app_id, &template.key).await?.len() > 1 {
+            return Err(Error::ValidationError(
+                format!("Localized templates of {} must be deleted before its default template", template.key)
+            ));
+        }
+
+        let conn = Config::get_database_conn().await?;
+        sqlx::query(
+            "DELETE FROM `notification_templates` WHERE id = ?"
+        )
+            .bind::<i32>(template_id.into())
+            .execute(&conn)
+            .await?;
+        self.redis_service.increment_template_generation(app_id).await?;
+
+        Ok(())
+    }
+
+    // Categories of the database templates and of the notifications file keys they do not override.
+    pub async fn fetch_categories(&mut self, app_id: i32) -> Result<Vec<String>, Error> {
+        let conn = Config::get_database_conn().await?;
+        let templates = sqlx::query_as::<_, NotificationTemplateFromRow>(
+            "SELECT * FROM `notification_templates` WHERE app_id = ? AND locale = ''"
+        )
+            .bind::<i32>(app_id)
+            .fetch_all(&conn)
+            .await?;
+
+        let mut categories: BTreeSet<String> = templates.iter()
+            .filter_map(|template| template.category.clone())
+            .collect();
+        for (key, information) in &self.get_catalog(app_id).await?.0 {
+            if templates.iter().any(|template| &template.key == key) {
+                continue;
+            }
+            categories.extend(information.category.clone());
+        }
+
+        Ok(categories.into_iter().collect())
+    }
+}
+
+impl NotificationTemplateService {
+    async fn get_notification_information_or_error(&mut self, app_id: i32, notification_type: &str) -> Result<NotificationInformation, Error> {
+        match self.get_notification_information_by_key(app_id, notification_type).await? {
+            Some(information) => Ok(information),
+            None => Err(Error::ValidationError(format!("Unknown notification type: {}", notification_type)))
+        }
+    }
+
+    async fn get_catalog(&mut self, app_id: i32) -> Result<Arc<NotificationsRule>, Error> {
+        let app = AppService::new().find_app_by_id(app_id.into()).await?;
+
+        NotificationCatalog::get_by_path(&app.get_notification_resources_path()?)
+    }
+
+    // Keys without templates are cached too, sparing the database for the keys of the notifications file.
+    async fn fetch_templates_by_key(&mut self, app_id: i32, key: &str) -> Result<ListOfNotificationTemplateFromRow, Error> {
+        let generation = self.redis_service.get_template_generation(app_id).await?;
+        let cache_key = (app_id, key.to_string());
+        if let Some((cached_generation, templates)) = Self::get_templates_cache().read().unwrap().get(&cache_key) {
+            if *cached_generation == generation {
+                return Ok(templates.clone());
+            }
+        }
+
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_as::<_, NotificationTemplateFromRow>(
+            "SELECT * FROM `notification_templates` WHERE app_id = ? AND `key` = ?"
+        )
+            .bind::<i32>(app_id)
+            .bind::<&str>(key)
+            .fetch_all(&conn)
+            .await?;
+        Self::get_templates_cache().write().unwrap().insert(cache_key, (generation, result.clone()));
+
+        Ok(result)
+    }
+
+    fn get_templates_cache() -> &'static TemplatesCache {
+        TEMPLATES.get_or_init(|| RwLock::new(HashMap::new()))
+    }
+
+    async fn insert(&mut self, app_id: i32, key: &str, locale: &str, template: NotificationTemplate) -> Result<i32, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query(
+            r#"
+                INSERT INTO `notification_templates`
+                    (app_id, `key`, locale, title, body, params, optional_params, respect_quiet_hours, category, digest, options, platform_overrides, creation_date, update_date)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, NOW(), NOW())
+            "#
+        )
+            .bind::<i32>(app_id)
+            .bind::<&str>(key)
+            .bind::<&str>(locale)
+            .bind::<String>(template.title)
+            .bind::<String>(template.body)
+            .bind::<Option<Json<Vec<String>>>>(template.params.map(Json))
+            .bind::<Option<Json<Vec<String>>>>(template.optional_params.map(Json))
+            .bind::<bool>(template.respect_quiet_hours)
+            .bind::<Option<String>>(template.category)
+            .bind::<Option<Json<DigestInformation>>>(template.digest.map(Json))
+            .bind::<Json<NotificationOptions>>(Json(template.options))
+            .bind::<Json<HashMap<String, PlatformNotificationInformation>>>(Json(template.platform_overrides))
+            .execute(&conn)
+            .await?;
+        self.redis_service.increment_template_generation(app_id).await?;
+
+        Ok(result.last_insert_id() as i32)
+    }
+
+    fn check_template(key: &str, locale: &str, template: &NotificationTemplate) -> Result<(), Error> {
+        if key.trim().is_empty() {
+            return Err(Error::ValidationError("key cannot be empty".to_string()));
+        }
+        if !locale.is_empty() && !Locale(locale.to_string()).is_valid() {
+            return Err(Error::ValidationError("locale must be a valid language tag (ex: fr, en-US)".to_string()));
+        }
+        // Params, quiet hours, category and options belong to the notification type, only its default template carries them.
+        let has_type_settings = template.params.is_some()
+            || template.optional_params.is_some()
+            || template.respect_quiet_hours
+            || template.category.is_some()
+            || template.options != NotificationOptions::default();
+        if !locale.is_empty() && has_type_settings {
+            return Err(Error::ValidationError(
+                "params, optional_params, respect_quiet_hours, category and options can only be set on the default locale template".to_string()
+            ));
+        }
+        if let Some(digest) = &template.digest {
+            digest.validate(!locale.is_empty())?;
+        }
+
+        NotificationInformation {
+            title: template.title.clone(),
+            body: template.body.clone(),
+            params: template.params.clone(),
+            optional_params: template.optional_params.clone(),
+            respect_quiet_hours: template.respect_quiet_hours,
+            category: template.category.clone(),
+            digest: template.digest.clone(),
+            version: None,
+            locale: None,
+            options: template.options.clone(),
+            platforms: template.platform_overrides.clone(),
+            locales: HashMap::new()
+        }.validate(key)
+    }
+}
+
+fn join(values: &[&String]) -> String {
+    values.iter().map(|value| value.as_str()).collect::<Vec<&str>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn information(params: serde_json::Value, optional_params: serde_json::Value) -> NotificationInformation {
+        serde_json::from_value(json!({
+            "title": "Order {{ order_id }}",
+            "body": "Shipped",
+            "params": params,
+            "optional_params": optional_params
+        })).unwrap()
+    }
+
+    fn template_data(keys: &[&str]) -> Option<HashMap<String, String>> {
+        Some(keys.iter().map(|key| (key.to_string(), "value".to_string())).collect())
+    }
+
+    #[test]
+    fn get_template_data_violations_accepts_declared_params() {
+        let information = information(json!(["order_id", "carrier"]), json!(["eta"]));
+
+        assert_eq!(information.get_template_data_violations(&template_data(&["order_id", "carrier"])), None);
+        assert_eq!(information.get_template_data_violations(&template_data(&["carrier", "order_id", "eta"])), None);
+    }
+
+    #[test]
+    fn get_template_data_violations_lists_missing_and_unknown_params() {
+        let information = information(json!(["order_id", "carrier"]), json!(["eta"]));
+
+        assert_eq!(
+            information.get_template_data_violations(&None),
+            Some("missing params [carrier, order_id]".to_string())
+        );
+        assert_eq!(
+            information.get_template_data_violations(&template_data(&["order_id", "eta", "coupon", "amount"])),
+            Some("missing params [carrier], unknown params [amount, coupon]".to_string())
+        );
+    }
+
+    #[test]
+    fn get_template_data_violations_rejects_any_data_without_declared_params() {
+        let information = information(json!(null), json!(null));
+
+        assert_eq!(information.get_template_data_violations(&None), None);
+        assert_eq!(information.get_template_data_violations(&template_data(&[])), None);
+        assert_eq!(
+            information.get_template_data_violations(&template_data(&["order_id"])),
+            Some("unknown params [order_id]".to_string())
+        );
+    }
+}
\ No newline at end of file
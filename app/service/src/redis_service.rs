@@ -1,10 +1,17 @@
-use redis::{Client, Commands, Connection, RedisResult};
+use chrono::{DateTime, Utc};
+use redis::{Client, Commands, Connection, RedisResult, Script};
 use core::{
     config::Config,
     error::Error
 };
 use redis::streams::StreamRangeReply;
-use crate::rows::notification_row::NotificationRow;
+use crate::{
+    notification_rate::{NotificationRate, Throttle},
+    rows::{
+        idempotent_response_row::IdempotentResponseRow,
+        notification_row::NotificationRow
+    }
+};
 
 pub type ListOfRedisIdWithNotificationRow = Vec<(String, NotificationRow)>;
 pub type ListOfRedisIdWithNotificationRowRef<'a> = Vec<(&'a String, &'a NotificationRow)>;
@@ -32,6 +39,26 @@ impl RedisService {
         Ok(())
     }
 
+    // Enqueued in a single transaction, either every notification is queued or none is.
+    pub async fn create_notifications(&mut self, notifications: &[NotificationRow]) -> Result<(), Error> {
+        if notifications.is_empty() {
+            return Ok(())
+        }
+
+        let mut pipeline = redis::pipe();
+        pipeline.atomic();
+        for notification in notifications {
+            pipeline.xadd(
+                Self::NOTIFICATION_STREAM_KEY,
+                "*",
+                &[("notification", serde_json::to_string(notification).unwrap())]
+            ).ignore();
+        }
+        let _: () = pipeline.query(&mut self.client)?;
+
+        Ok(())
+    }
+
     pub async fn fetch_notification_to_send<'a>(&mut self) -> Result<ListOfRedisIdWithNotificationRow, Error> {
         let result: RedisResult<StreamRangeReply> = self.client
             .xrange_count(
@@ -68,13 +95,349 @@ impl RedisService {
 
         Ok(())
     }
+
+    pub async fn defer_notification(
+        &mut self,
+        id: &String,
+        notification: &NotificationRow,
+        until: DateTime<Utc>
+    ) -> Result<(), Error> {
+        let _: () = self.client.hset(
+            Self::DEFERRED_NOTIFICATION_HASH_KEY,
+            id,
+            serde_json::to_string(notification).unwrap()
+        )?;
+        let _: () = self.client.zadd(
+            Self::DEFERRED_NOTIFICATION_SET_KEY,
+            id,
+            until.timestamp()
+        )?;
+
+        Ok(())
+    }
+
+    // Quiet hours hold a notification for hours, its campaign does not count it as waiting to be sent meanwhile.
+    pub async fn defer_quiet_hours_notification(
+        &mut self,
+        id: &String,
+        notification: &NotificationRow,
+        until: DateTime<Utc>
+    ) -> Result<(), Error> {
+        self.defer_notification(id, notification, until).await?;
+        if let Some(campaign_id) = notification.campaign_id {
+            let key = format!("{}:{}", Self::CAMPAIGN_DEFERRED_KEY_PREFIX, campaign_id);
+            let _: () = self.client.zadd(&key, id, until.timestamp())?;
+            // Quiet hours never last a whole day.
+            let _: () = self.client.expire(&key, 86_400)?;
+        }
+
+        Ok(())
+    }
+
+    // The notifications of the campaign still held by quiet hours, the released ones are waiting in the stream again.
+    pub async fn count_deferred_campaign_notifications(&mut self, campaign_id: i32) -> Result<i64, Error> {
+        let key = format!("{}:{}", Self::CAMPAIGN_DEFERRED_KEY_PREFIX, campaign_id);
+        let _: () = self.client.zrembyscore(&key, "-inf", Utc::now().timestamp())?;
+        let count: i64 = self.client.zcard(&key)?;
+
+        Ok(count)
+    }
+
+    pub async fn release_deferred_notifications(&mut self) -> Result<usize, Error> {
+        let ids: Vec<String> = self.client.zrangebyscore(
+            Self::DEFERRED_NOTIFICATION_SET_KEY,
+            "-inf",
+            Utc::now().timestamp()
+        )?;
+
+        for id in &ids {
+            // Only the consumer removing the id from the set releases it.
+            let removed: i32 = self.client.zrem(Self::DEFERRED_NOTIFICATION_SET_KEY, id)?;
+            if removed == 0 {
+                continue;
+            }
+
+            let notification: Option<String> = self.client.hget(Self::DEFERRED_NOTIFICATION_HASH_KEY, id)?;
+            if let Some(notification) = notification {
+                let _: () = self.client.xadd(
+                    Self::NOTIFICATION_STREAM_KEY,
+                    "*",
+                    &[("notification", notification)]
+                )?;
+            }
+
+            let _: () = self.client.hdel(Self::DEFERRED_NOTIFICATION_HASH_KEY, id)?;
+        }
+
+        Ok(ids.len())
+    }
+
+    // Adds the notification to the digest buffer of its user, which is due once the window of its first notification ends.
+    pub async fn buffer_digest_notification(
+        &mut self,
+        notification: &NotificationRow,
+        window: i64,
+        max_count: i64
+    ) -> Result<(), Error> {
+        let key = format!("{}:{}", Self::DIGEST_KEY_PREFIX, notification.get_digest_key());
+        let count: i64 = self.client.rpush(&key, serde_json::to_string(notification).unwrap())?;
+        if count == 1 {
+            let _: () = redis::cmd("ZADD")
+                .arg(Self::DIGEST_DUE_SET_KEY)
+                .arg("NX")
+                .arg(Utc::now().timestamp() + window)
+                .arg(&key)
+                .query(&mut self.client)?;
+        }
+
+        if count >= max_count {
+            self.flush_digest(&key)?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn release_due_digests(&mut self) -> Result<usize, Error> {
+        let keys: Vec<String> = self.client.zrangebyscore(
+            Self::DIGEST_DUE_SET_KEY,
+            "-inf",
+            Utc::now().timestamp()
+        )?;
+
+        let mut released = 0;
+        for key in &keys {
+            if self.flush_digest(key)? {
+                released += 1;
+            }
+        }
+
+        Ok(released)
+    }
+
+    // Takes a token from the bucket of the throttle and returns 0, or the milliseconds to wait before one is available.
+    pub async fn take_token(&mut self, throttle: &Throttle) -> Result<i64, Error> {
+        self.invoke_token_bucket(format!("{}:{}", Self::THROTTLE_KEY_PREFIX, throttle.key), &throttle.rate, throttle.rate.get_throttle_capacity())
+    }
+
+    // Same bucket as the throttles, shared by every api replica for a given client.
+    pub async fn take_api_token(&mut self, client: &str, rate: &NotificationRate) -> Result<i64, Error> {
+        self.invoke_token_bucket(format!("{}:{}", Self::API_RATE_LIMIT_KEY_PREFIX, client), rate, rate.get_capacity())
+    }
+
+    // Counts the notifications sent to the user during the current hour, this one included.
+    pub async fn increment_hourly_user_notifications(&mut self, user_id: i32) -> Result<i64, Error> {
+        let key = format!("{}:{}:{}", Self::USER_HOURLY_COUNT_KEY_PREFIX, user_id, Utc::now().timestamp() / 3600);
+        let count: i64 = self.client.incr(&key, 1)?;
+        if count == 1 {
+            let _: () = self.client.expire(&key, 3600)?;
+        }
+
+        Ok(count)
+    }
+
+    // Gives back the count of a notification which was not sent, the counter is left alone once its hour expired.
+    pub async fn decrement_hourly_user_notifications(&mut self, user_id: i32) -> Result<(), Error> {
+        let _: i64 = Script::new(Self::DECREMENT_EXISTING_SCRIPT)
+            .key(format!("{}:{}:{}", Self::USER_HOURLY_COUNT_KEY_PREFIX, user_id, Utc::now().timestamp() / 3600))
+            .invoke(&mut self.client)?;
+
+        Ok(())
+    }
+
+    // Returns false when a notification already claimed the key during the window.
+    pub async fn claim_dedup_key(&mut self, key: &str, window: i64) -> Result<bool, Error> {
+        let claimed: Option<String> = redis::cmd("SET")
+            .arg(format!("{}:{}", Self::DEDUP_KEY_PREFIX, key))
+            .arg(Utc::now().timestamp())
+            .arg("NX")
+            .arg("EX")
+            .arg(window)
+            .query(&mut self.client)?;
+
+        Ok(claimed.is_some())
+    }
+
+    // Lets a retry or a later notification with the same key through when the claiming one was not sent.
+    pub async fn release_dedup_key(&mut self, key: &str) -> Result<(), Error> {
+        let _: () = self.client.del(format!("{}:{}", Self::DEDUP_KEY_PREFIX, key))?;
+
+        Ok(())
+    }
+
+    // Reserves the key for the request, or returns the row of the request which already reserved it.
+    pub async fn reserve_idempotency_key(
+        &mut self,
+        key: &str,
+        row: &IdempotentResponseRow,
+        ttl: u64
+    ) -> Result<Option<IdempotentResponseRow>, Error> {
+        let key = format!("{}:{}", Self::IDEMPOTENCY_KEY_PREFIX, key);
+        // The existing row may expire between both commands, the key is then reserved again.
+        loop {
+            let reserved: Option<String> = redis::cmd("SET")
+                .arg(&key)
+                .arg(serde_json::to_string(row).unwrap())
+                .arg("NX")
+                .arg("EX")
+                .arg(ttl)
+                .query(&mut self.client)?;
+            if reserved.is_some() {
+                return Ok(None)
+            }
+
+            let existing: Option<String> = self.client.get(&key)?;
+            if let Some(existing) = existing {
+                return Ok(serde_json::from_str::<IdempotentResponseRow>(&existing).ok())
+            }
+        }
+    }
+
+    pub async fn store_idempotent_response(&mut self, key: &str, row: &IdempotentResponseRow, ttl: u64) -> Result<(), Error> {
+        let _: () = self.client.set_ex(
+            format!("{}:{}", Self::IDEMPOTENCY_KEY_PREFIX, key),
+            serde_json::to_string(row).unwrap(),
+            ttl
+        )?;
+
+        Ok(())
+    }
+
+    pub async fn release_idempotency_key(&mut self, key: &str) -> Result<(), Error> {
+        let _: () = self.client.del(format!("{}:{}", Self::IDEMPOTENCY_KEY_PREFIX, key))?;
+
+        Ok(())
+    }
+
+    // Bumped on every template write, every process then reloads the cached templates of the app.
+    pub async fn get_template_generation(&mut self, app_id: i32) -> Result<i64, Error> {
+        let generation: Option<i64> = self.client.get(format!("{}:{}", Self::TEMPLATE_GENERATION_KEY_PREFIX, app_id))?;
+
+        Ok(generation.unwrap_or_default())
+    }
+
+    pub async fn increment_template_generation(&mut self, app_id: i32) -> Result<(), Error> {
+        let _: () = self.client.incr(format!("{}:{}", Self::TEMPLATE_GENERATION_KEY_PREFIX, app_id), 1)?;
+
+        Ok(())
+    }
+
+    pub async fn get_next_throttle_key(&mut self, prefix: &str) -> Result<String, Error> {
+        let sequence: i64 = self.client.incr(Self::THROTTLE_SEQUENCE_KEY, 1)?;
+
+        Ok(format!("{}:{}", prefix, sequence))
+    }
 }
 
 impl RedisService {
     const NOTIFICATION_STREAM_KEY: &'static str = "notifications";
+    const DEFERRED_NOTIFICATION_SET_KEY: &'static str = "notifications:deferred";
+    const DEFERRED_NOTIFICATION_HASH_KEY: &'static str = "notifications:deferred:rows";
+    const CAMPAIGN_DEFERRED_KEY_PREFIX: &'static str = "notifications:deferred:campaign";
+    const THROTTLE_KEY_PREFIX: &'static str = "notifications:throttle";
+    const THROTTLE_SEQUENCE_KEY: &'static str = "notifications:throttle:sequence";
+    const API_RATE_LIMIT_KEY_PREFIX: &'static str = "api:rate_limit";
+    const USER_HOURLY_COUNT_KEY_PREFIX: &'static str = "notifications:user_hourly_count";
+    const IDEMPOTENCY_KEY_PREFIX: &'static str = "api:idempotency";
+    const DEDUP_KEY_PREFIX: &'static str = "notifications:dedup";
+    const TEMPLATE_GENERATION_KEY_PREFIX: &'static str = "notifications:templates:generation";
+    const DIGEST_KEY_PREFIX: &'static str = "notifications:digest";
+    const DIGEST_DUE_SET_KEY: &'static str = "notifications:digest:due";
+    // The buffer is read and emptied at once, notifications buffered afterwards start a new digest.
+    // Notifications buffered since the buffer was read are kept, and due right away.
+    const TRIM_DIGEST_SCRIPT: &'static str = r#"
+        redis.call('LTRIM', KEYS[2], ARGV[1], -1)
+        if redis.call('LLEN', KEYS[2]) > 0 then
+            redis.call('ZADD', KEYS[1], 'NX', ARGV[2], KEYS[2])
+        end
+
+        return 1
+    "#;
+    const DECREMENT_EXISTING_SCRIPT: &'static str = r#"
+        if redis.call('EXISTS', KEYS[1]) == 0 then
+            return 0
+        end
+
+        return redis.call('DECR', KEYS[1])
+    "#;
+    // Redis time is used so every consumer refills the bucket with the same clock.
+    const TOKEN_BUCKET_SCRIPT: &'static str = r#"
+        local capacity = tonumber(ARGV[1])
+        local tokens_per_millisecond = tonumber(ARGV[2])
+        local time = redis.call('TIME')
+        local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+
+        local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'timestamp')
+        local tokens = tonumber(bucket[1]) or capacity
+        local timestamp = tonumber(bucket[2]) or now
+        tokens = math.min(capacity, tokens + math.max(0, now - timestamp) * tokens_per_millisecond)
+
+        local wait = 0
+        if tokens >= 1 then
+            tokens = tokens - 1
+        else
+            wait = math.ceil((1 - tokens) / tokens_per_millisecond)
+        end
+
+        redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'timestamp', now)
+        redis.call('PEXPIRE', KEYS[1], math.ceil(capacity / tokens_per_millisecond) + 60000)
+
+        return wait
+    "#;
 }
 
 impl RedisService {
+    fn invoke_token_bucket(&mut self, key: String, rate: &NotificationRate, capacity: i64) -> Result<i64, Error> {
+        let wait: i64 = Script::new(Self::TOKEN_BUCKET_SCRIPT)
+            .key(key)
+            .arg(capacity)
+            .arg(rate.get_tokens_per_millisecond())
+            .invoke(&mut self.client)?;
+
+        Ok(wait)
+    }
+
+    // Sends the buffered notifications back to the stream as a single digest, returns false when the buffer was empty.
+    // The buffer is only trimmed once the digest is in the stream, a failing flush loses none of its notifications.
+    fn flush_digest(&mut self, key: &str) -> Result<bool, Error> {
+        // Only the consumer removing the key from the due set flushes the buffer.
+        let removed: i32 = self.client.zrem(Self::DIGEST_DUE_SET_KEY, key)?;
+        if removed == 0 {
+            return Ok(false);
+        }
+
+        let rows: Vec<String> = self.client.lrange(key, 0, -1)?;
+        let digest_row = Self::get_digest_row(&rows);
+        if let Some(digest_row) = &digest_row {
+            let result: RedisResult<()> = self.client.xadd(
+                Self::NOTIFICATION_STREAM_KEY,
+                "*",
+                &[("notification", serde_json::to_string(digest_row).unwrap())]
+            );
+            if let Err(err) = result {
+                let _: () = self.client.zadd(Self::DIGEST_DUE_SET_KEY, key, Utc::now().timestamp())?;
+                return Err(err.into());
+            }
+        }
+
+        let _: i32 = Script::new(Self::TRIM_DIGEST_SCRIPT)
+            .key(Self::DIGEST_DUE_SET_KEY)
+            .key(key)
+            .arg(rows.len())
+            .arg(Utc::now().timestamp())
+            .invoke(&mut self.client)?;
+
+        Ok(digest_row.is_some())
+    }
+
+    // Unreadable rows are left out of the digest, they are trimmed with the others.
+    fn get_digest_row(rows: &[String]) -> Option<NotificationRow> {
+        let rows: Vec<NotificationRow> = rows.iter()
+            .filter_map(|row| serde_json::from_str::<NotificationRow>(row).ok())
+            .collect();
+
+        NotificationRow::from_digest(rows)
+    }
+
     fn get_client() -> Result<Connection, Error> {
         let conn = Client::open(Config::get_redis_uri())
             .expect("Invalid connection URL")
@@ -83,4 +446,36 @@ impl RedisService {
 
         Ok(conn)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn buffered_row(name: &str) -> String {
+        json!({
+            "app_id": 1,
+            "user_id": 7,
+            "notification_type": "NewMessages",
+            "number_of_tries": 0,
+            "template_data": json!({ "name": name }).to_string(),
+            "campaign_id": 3
+        }).to_string()
+    }
+
+    #[test]
+    fn get_digest_row_leaves_unreadable_rows_out() {
+        let rows = vec![buffered_row("Ana"), "{".to_string(), buffered_row("Bob")];
+
+        let digest_row = RedisService::get_digest_row(&rows).unwrap();
+        assert_eq!(digest_row.digest.unwrap().count, 2);
+        assert_eq!(digest_row.campaign_id, None);
+    }
+
+    #[test]
+    fn get_digest_row_is_none_without_readable_rows() {
+        assert!(RedisService::get_digest_row(&[]).is_none());
+        assert!(RedisService::get_digest_row(&["{".to_string()]).is_none());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,31 @@
+use std::str::FromStr;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::{FromRow, types::Json};
+use types::enums::ApiKeyScope;
+
+#[derive(Serialize, Deserialize, Clone, Debug, FromRow)]
+pub struct ApiKeyFromRow {
+    pub id: i32,
+    pub app_id: i32,
+    pub name: String,
+    pub key_prefix: String,
+    pub key_hash: String,
+    pub scopes: Json<Vec<String>>,
+    pub last_used_date: Option<DateTime<Utc>>,
+    pub revocation_date: Option<DateTime<Utc>>,
+    pub creation_date: DateTime<Utc>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, FromRow)]
+pub struct CountApiKeyFromRow {
+    pub total_count: i32
+}
+
+impl ApiKeyFromRow {
+    pub fn get_scopes(&self) -> Vec<ApiKeyScope> {
+        self.scopes.0.iter()
+            .filter_map(|scope| ApiKeyScope::from_str(scope).ok())
+            .collect()
+    }
+}
\ No newline at end of file
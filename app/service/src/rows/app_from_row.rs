@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::FromRow;
+use core::{
+    config::Config,
+    error::Error
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, FromRow)]
+pub struct AppFromRow {
+    pub id: i32,
+    pub name: String,
+    pub google_service_account_credentials_path: Option<String>,
+    pub notification_resources_path: Option<String>,
+    pub device_registration_secret: Option<String>,
+    pub creation_date: DateTime<Utc>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, FromRow)]
+pub struct CountAppFromRow {
+    pub total_count: i32
+}
+
+impl AppFromRow {
+    pub fn get_google_service_account_credentials_path(&self) -> String {
+        self.google_service_account_credentials_path.clone()
+            .unwrap_or_else(Config::get_google_service_account_credentials_path)
+    }
+
+    pub fn get_notification_resources_path(&self) -> Result<String, Error> {
+        match &self.notification_resources_path {
+            Some(path) => Ok(path.clone()),
+            None => Config::get_notification_resources_path()
+        }
+    }
+
+    pub fn get_device_registration_secret(&self) -> Option<String> {
+        self.device_registration_secret.clone()
+            .or_else(Config::get_device_registration_secret)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,63 @@
+use std::{
+    collections::HashMap,
+    str::FromStr
+};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::{FromRow, types::Json};
+use types::{
+    enums::CampaignStatus,
+    extra_data::ExtraData
+};
+use crate::{
+    notification_options::NotificationOptions,
+    notification_rate::{NotificationRate, Throttle}
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, FromRow)]
+pub struct CampaignFromRow {
+    pub id: i32,
+    pub app_id: i32,
+    pub name: String,
+    pub notification_type: String,
+    pub audience: Option<String>,
+    pub extra_data: Option<Json<ExtraData>>,
+    pub template_data: Option<Json<HashMap<String, String>>>,
+    pub options: Option<Json<NotificationOptions>>,
+    pub rate: Option<Json<NotificationRate>>,
+    pub status: String,
+    pub scheduled_date: Option<DateTime<Utc>>,
+    pub last_user_id: i32,
+    pub queued_count: i32,
+    pub start_date: Option<DateTime<Utc>>,
+    pub completion_date: Option<DateTime<Utc>>,
+    pub creation_date: DateTime<Utc>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, FromRow)]
+pub struct CountCampaignFromRow {
+    pub total_count: i32
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, FromRow)]
+pub struct CampaignStatusCountFromRow {
+    pub status: String,
+    pub total_count: i32
+}
+
+impl CampaignFromRow {
+    pub fn get_status(&self) -> CampaignStatus {
+        CampaignStatus::from_str(&self.status).unwrap_or(CampaignStatus::Draft)
+    }
+
+    pub fn get_options(&self) -> NotificationOptions {
+        self.options.as_ref().map(|options| options.0.clone()).unwrap_or_default()
+    }
+
+    pub fn get_throttle(&self) -> Option<Throttle> {
+        self.rate.as_ref().map(|rate| Throttle {
+            key: format!("campaign:{}", self.id),
+            rate: rate.0.clone()
+        })
+    }
+}
\ No newline at end of file
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdempotentResponseRow {
+    pub fingerprint: String,
+    // Empty while the first request is processed.
+    pub status: Option<u16>,
+    pub content_type: Option<String>,
+    pub body: Option<String>,
+}
+
+impl IdempotentResponseRow {
+    pub fn new(fingerprint: String) -> Self {
+        IdempotentResponseRow {
+            fingerprint,
+            status: None,
+            content_type: None,
+            body: None
+        }
+    }
+
+    // Identifies the request a key was first used for.
+    pub fn get_fingerprint(method: &str, path: &str, body: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(method.as_bytes());
+        hasher.update(path.as_bytes());
+        hasher.update(body);
+
+        hex::encode(hasher.finalize())
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.status.is_some()
+    }
+}
\ No newline at end of file
@@ -1,2 +1,8 @@
 pub mod user_from_row;
-pub mod notification_row;
\ No newline at end of file
+pub mod notification_row;
+pub mod schedule_from_row;
+pub mod notification_template_from_row;
+pub mod campaign_from_row;
+pub mod api_key_from_row;
+pub mod app_from_row;
+pub mod idempotent_response_row;
\ No newline at end of file
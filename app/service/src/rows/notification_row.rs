@@ -1,19 +1,49 @@
-use chrono::Utc;
+use std::collections::{BTreeMap, HashMap};
+use chrono::{DateTime, Duration, Utc};
 use redis::{from_redis_value, Value};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use sha2::{Digest, Sha256};
 use types::{
+    extra_data::ExtraData,
     enums::NotificationStatus,
     dates::{UpdateDate, CreationDate}
 };
-use crate::notification_history_service::NotificationHistory;
+use crate::{
+    app_service::AppService,
+    notification_history_service::NotificationHistory,
+    notification_options::NotificationOptions,
+    notification_rate::Throttle,
+    notification_template_service::NotificationInformation
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotificationRow {
+    #[serde(default = "default_app_id")]
+    pub app_id: i32,
     pub user_id: i32,
     pub notification_type: String,
     pub number_of_tries: i32,
-    pub extra_data: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_extra_data")]
+    pub extra_data: Option<ExtraData>,
     pub template_data: Option<String>,
+    #[serde(default)]
+    pub options: NotificationOptions,
+    #[serde(default)]
+    pub creation_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub campaign_id: Option<i32>,
+    #[serde(default)]
+    pub throttle: Option<Throttle>,
+    // Set on the rows flushed from a digest buffer, which are sent as they are.
+    #[serde(default)]
+    pub digest: Option<NotificationDigest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationDigest {
+    pub count: usize,
+    // Template data of the buffered notifications, oldest first.
+    pub template_data: Vec<HashMap<String, String>>,
 }
 
 impl From<&Value> for NotificationRow {
@@ -31,24 +61,187 @@ impl From<std::option::Option<&redis::Value>> for NotificationRow {
 }
 
 impl NotificationRow {
-    pub fn as_sent_notification(&mut self) -> NotificationHistory {
-        self.transform_into_notification_history(NotificationStatus::Sent)
+    pub fn as_sent_notification(&mut self, information: &NotificationInformation) -> NotificationHistory {
+        let mut notification_history = self.transform_into_notification_history(NotificationStatus::Sent);
+        notification_history.template_version = information.version;
+        notification_history.template_locale = information.locale.clone();
+
+        notification_history
     }
 
     pub fn as_failed_notification(&mut self) -> NotificationHistory {
         self.transform_into_notification_history(NotificationStatus::Failed)
     }
 
+    pub fn as_canceled_notification(&mut self, reason: &str) -> NotificationHistory {
+        let mut notification_history = self.transform_into_notification_history(NotificationStatus::Canceled);
+        notification_history.reason = Some(reason.to_string());
+
+        notification_history
+    }
+
+    pub fn get_expiration_date(&self, ttl: Option<i64>) -> Option<DateTime<Utc>> {
+        match (self.creation_date, ttl) {
+            (Some(creation_date), Some(ttl)) => Some(creation_date + Duration::seconds(ttl)),
+            _ => None
+        }
+    }
+
+    // The buffered notifications in a single row, holding the extra data and options of the latest one.
+    // Each buffered notification already counts towards its campaign as digested, the digest belongs to none.
+    pub fn from_digest(rows: Vec<NotificationRow>) -> Option<NotificationRow> {
+        let mut digest_row = rows.last()?.clone();
+        digest_row.number_of_tries = 0;
+        digest_row.creation_date = Some(Utc::now());
+        digest_row.campaign_id = None;
+        digest_row.digest = Some(NotificationDigest {
+            count: rows.len(),
+            template_data: rows.iter()
+                .map(|row| row.template_data.as_ref()
+                    .and_then(|template_data| serde_json::from_str::<HashMap<String, String>>(template_data).ok())
+                    .unwrap_or_default()
+                )
+                .collect()
+        });
+
+        Some(digest_row)
+    }
+
+    pub fn get_digest_key(&self) -> String {
+        format!("{}:{}:{}", self.app_id, self.user_id, self.notification_type)
+    }
+
+    // Keys are scoped to the user, a derived key hashes the type with the template data sorted by name.
+    // Digests gather different notifications, they are never deduplicated.
+    pub fn get_dedup_key(&self, options: &NotificationOptions) -> Option<String> {
+        if !options.is_deduplicated() || self.digest.is_some() {
+            return None;
+        }
+
+        let dedup_key = match &options.dedup_key {
+            Some(dedup_key) => dedup_key.clone(),
+            None => {
+                let template_data: BTreeMap<String, String> = self.template_data.as_ref()
+                    .and_then(|template_data| serde_json::from_str::<HashMap<String, String>>(template_data).ok())
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect();
+                let mut hasher = Sha256::new();
+                hasher.update(self.notification_type.as_bytes());
+                hasher.update(serde_json::to_string(&template_data).unwrap_or_default().as_bytes());
+
+                hex::encode(hasher.finalize())
+            }
+        };
+
+        Some(format!("{}:{}:{}", self.app_id, self.user_id, dedup_key))
+    }
+
     fn transform_into_notification_history(
         &mut self,
         notification_status: NotificationStatus
     ) -> NotificationHistory {
         NotificationHistory {
+            app_id: self.app_id,
             user_id: self.clone().user_id.into(),
-            notification_type: self.clone().notification_type.into(),
+            notification_type: self.clone().notification_type,
             notification_status,
+            template_version: None,
+            template_locale: None,
+            reason: None,
+            campaign_id: self.campaign_id,
             creation_date: CreationDate { 0: Utc::now() },
             update_date: UpdateDate { 0: Utc::now() },
         }
     }
+}
+
+// Rows queued before apps existed belong to the default app.
+fn default_app_id() -> i32 {
+    AppService::DEFAULT_APP_ID
+}
+
+// Rows queued before extra_data became structured hold it as a JSON encoded string.
+fn deserialize_extra_data<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<ExtraData>, D::Error> {
+    match Option::<serde_json::Value>::deserialize(deserializer)? {
+        Some(serde_json::Value::String(json)) => serde_json::from_str(&json).map(Some).map_err(serde::de::Error::custom),
+        Some(value) => serde_json::from_value(value).map(Some).map_err(serde::de::Error::custom),
+        None => Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn row(template_data: &str) -> NotificationRow {
+        serde_json::from_value(json!({
+            "app_id": 1,
+            "user_id": 7,
+            "notification_type": "NewMessages",
+            "number_of_tries": 0,
+            "template_data": template_data
+        })).unwrap()
+    }
+
+    fn deduplicated(dedup_key: Option<&str>) -> NotificationOptions {
+        NotificationOptions {
+            dedup_key: dedup_key.map(|dedup_key| dedup_key.to_string()),
+            deduplicate: Some(true),
+            ..NotificationOptions::default()
+        }
+    }
+
+    #[test]
+    fn get_dedup_key_does_not_depend_on_the_template_data_order() {
+        let dedup_key = row(r#"{"count":"3","name":"Ana"}"#).get_dedup_key(&deduplicated(None)).unwrap();
+
+        assert!(dedup_key.starts_with("1:7:"));
+        assert_eq!(row(r#"{"name":"Ana","count":"3"}"#).get_dedup_key(&deduplicated(None)), Some(dedup_key.clone()));
+        assert_ne!(row(r#"{"count":"4","name":"Ana"}"#).get_dedup_key(&deduplicated(None)), Some(dedup_key));
+    }
+
+    #[test]
+    fn get_dedup_key_prefers_the_given_key() {
+        let options = deduplicated(Some("welcome"));
+
+        assert_eq!(row(r#"{"count":"3"}"#).get_dedup_key(&options), Some("1:7:welcome".to_string()));
+        assert_eq!(row(r#"{"count":"4"}"#).get_dedup_key(&options), Some("1:7:welcome".to_string()));
+        assert_eq!(row("{}").get_dedup_key(&NotificationOptions { deduplicate: Some(false), ..options }), None);
+        assert_eq!(row("{}").get_dedup_key(&NotificationOptions::default()), None);
+    }
+
+    #[test]
+    fn get_dedup_key_skips_digests() {
+        let mut digest_row = row(r#"{"count":"3"}"#);
+        digest_row.digest = Some(NotificationDigest { count: 2, template_data: Vec::new() });
+
+        assert_eq!(digest_row.get_dedup_key(&deduplicated(None)), None);
+        assert_eq!(digest_row.get_dedup_key(&deduplicated(Some("welcome"))), None);
+    }
+
+    #[test]
+    fn from_digest_gathers_the_template_data_oldest_first() {
+        let mut oldest = row(r#"{"name":"Ana"}"#);
+        oldest.number_of_tries = 2;
+        let latest = row(r#"{"name":"Bob"}"#);
+
+        let digest_row = NotificationRow::from_digest(vec![oldest, latest]).unwrap();
+        let digest = digest_row.digest.unwrap();
+        assert_eq!(digest.count, 2);
+        assert_eq!(digest.template_data[0]["name"], "Ana");
+        assert_eq!(digest.template_data[1]["name"], "Bob");
+        assert_eq!(digest_row.template_data, Some(r#"{"name":"Bob"}"#.to_string()));
+        assert_eq!(digest_row.number_of_tries, 0);
+    }
+
+    #[test]
+    fn from_digest_belongs_to_no_campaign() {
+        let mut buffered = row(r#"{"name":"Ana"}"#);
+        buffered.campaign_id = Some(3);
+
+        assert_eq!(NotificationRow::from_digest(vec![buffered]).unwrap().campaign_id, None);
+        assert!(NotificationRow::from_digest(Vec::new()).is_none());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::{FromRow, types::Json};
+use crate::{
+    notification_options::NotificationOptions,
+    notification_template_service::{DigestInformation, PlatformNotificationInformation}
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, FromRow)]
+pub struct NotificationTemplateFromRow {
+    pub id: i32,
+    pub app_id: i32,
+    pub key: String,
+    pub locale: String,
+    pub title: String,
+    pub body: String,
+    pub params: Option<Json<Vec<String>>>,
+    pub optional_params: Option<Json<Vec<String>>>,
+    pub respect_quiet_hours: bool,
+    pub category: Option<String>,
+    pub digest: Option<Json<DigestInformation>>,
+    pub options: Option<Json<NotificationOptions>>,
+    pub platform_overrides: Option<Json<HashMap<String, PlatformNotificationInformation>>>,
+    pub version: i32,
+    pub creation_date: DateTime<Utc>,
+    pub update_date: DateTime<Utc>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, FromRow)]
+pub struct CountNotificationTemplateFromRow {
+    pub total_count: i32
+}
+
+impl NotificationTemplateFromRow {
+    pub fn is_default_locale(&self) -> bool {
+        self.locale.is_empty()
+    }
+
+    pub fn get_options(&self) -> NotificationOptions {
+        self.options.as_ref().map(|options| options.0.clone()).unwrap_or_default()
+    }
+
+    pub fn get_digest(&self) -> Option<DigestInformation> {
+        self.digest.as_ref().map(|digest| digest.0.clone())
+    }
+
+    pub fn get_platform_overrides(&self) -> HashMap<String, PlatformNotificationInformation> {
+        self.platform_overrides.as_ref().map(|overrides| overrides.0.clone()).unwrap_or_default()
+    }
+}
\ No newline at end of file
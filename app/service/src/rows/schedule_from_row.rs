@@ -0,0 +1,27 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::{FromRow, types::Json};
+use types::extra_data::ExtraData;
+
+#[derive(Serialize, Deserialize, Clone, Debug, FromRow)]
+pub struct ScheduleFromRow {
+    pub id: i32,
+    pub app_id: i32,
+    pub cron_expression: String,
+    pub timezone: String,
+    pub notification_type: String,
+    pub user_ids: Option<Json<Vec<i32>>>,
+    pub audience: Option<String>,
+    pub extra_data: Option<Json<ExtraData>>,
+    pub template_data: Option<Json<HashMap<String, String>>>,
+    pub is_paused: bool,
+    pub next_run_date: DateTime<Utc>,
+    pub last_run_date: Option<DateTime<Utc>>,
+    pub creation_date: DateTime<Utc>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, FromRow)]
+pub struct CountScheduleFromRow {
+    pub total_count: i32
+}
\ No newline at end of file
@@ -1,15 +1,143 @@
+use std::str::FromStr;
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use serde::{Serialize, Deserialize};
 use sqlx::{FromRow};
 
 #[derive(Serialize, Deserialize, Clone, Debug, FromRow)]
 pub struct UserFromRow {
     pub id: i32,
+    pub app_id: i32,
     pub device_id: String,
     pub device_type: String,
-    pub token: Option<String>
+    pub token: Option<String>,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub quiet_hours_start: Option<NaiveTime>,
+    pub quiet_hours_end: Option<NaiveTime>
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, FromRow)]
 pub struct CountUserFromRow {
     pub total_count: i32
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, FromRow)]
+pub struct UserAttributeFromRow {
+    pub name: String,
+    pub value: String
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, FromRow)]
+pub struct UserPreferenceFromRow {
+    pub category: String,
+    pub channel: String,
+    pub enabled: bool
+}
+
+impl UserFromRow {
+    pub fn get_quiet_hours_end(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = self.quiet_hours_start?;
+        let end = self.quiet_hours_end?;
+        let timezone = Tz::from_str(self.timezone.as_ref()?).ok()?;
+
+        let local_now = now.with_timezone(&timezone);
+        let time = local_now.time();
+        let in_quiet_hours = if start <= end {
+            time >= start && time < end
+        } else {
+            time >= start || time < end
+        };
+
+        if !in_quiet_hours {
+            return None;
+        }
+
+        let mut end_date = local_now.date_naive();
+        if time >= end {
+            end_date = end_date.succ_opt()?;
+        }
+
+        // When the window ends inside a DST gap, wake up once the clock has moved forward.
+        let local_end = end_date.and_time(end);
+        let quiet_hours_end = timezone.from_local_datetime(&local_end).earliest()
+            .or_else(|| timezone.from_local_datetime(&(local_end + Duration::hours(1))).earliest())?;
+
+        Some(quiet_hours_end.with_timezone(&Utc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(timezone: Option<&str>, start: &str, end: &str) -> UserFromRow {
+        UserFromRow {
+            id: 1,
+            app_id: 1,
+            device_id: "device".to_string(),
+            device_type: "Android".to_string(),
+            token: None,
+            locale: None,
+            timezone: timezone.map(|timezone| timezone.to_string()),
+            quiet_hours_start: NaiveTime::from_str(start).ok(),
+            quiet_hours_end: NaiveTime::from_str(end).ok()
+        }
+    }
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn get_quiet_hours_end_is_none_outside_the_window() {
+        let user = user(Some("Europe/Paris"), "22:00:00", "07:00:00");
+
+        // 12:00 and 07:00 in Paris.
+        assert_eq!(user.get_quiet_hours_end(utc("2024-06-01T10:00:00Z")), None);
+        assert_eq!(user.get_quiet_hours_end(utc("2024-06-01T05:00:00Z")), None);
+    }
+
+    #[test]
+    fn get_quiet_hours_end_is_none_without_settings() {
+        let now = utc("2024-06-01T21:00:00Z");
+
+        assert_eq!(user(None, "22:00:00", "07:00:00").get_quiet_hours_end(now), None);
+        assert_eq!(user(Some("Mars/Olympus"), "22:00:00", "07:00:00").get_quiet_hours_end(now), None);
+        assert_eq!(user(Some("Europe/Paris"), "", "07:00:00").get_quiet_hours_end(now), None);
+    }
+
+    #[test]
+    fn get_quiet_hours_end_within_a_day() {
+        let user = user(Some("America/New_York"), "13:00:00", "14:00:00");
+
+        assert_eq!(user.get_quiet_hours_end(utc("2024-06-01T17:30:00Z")), Some(utc("2024-06-01T18:00:00Z")));
+    }
+
+    #[test]
+    fn get_quiet_hours_end_across_midnight() {
+        let user = user(Some("Europe/Paris"), "22:00:00", "07:00:00");
+
+        // Before midnight the window ends the next day, after midnight the same day.
+        assert_eq!(user.get_quiet_hours_end(utc("2024-06-01T21:00:00Z")), Some(utc("2024-06-02T05:00:00Z")));
+        assert_eq!(user.get_quiet_hours_end(utc("2024-06-02T01:00:00Z")), Some(utc("2024-06-02T05:00:00Z")));
+    }
+
+    #[test]
+    fn get_quiet_hours_end_across_a_dst_change() {
+        let user = user(Some("Europe/Paris"), "22:00:00", "07:00:00");
+
+        // Paris moves from UTC+2 to UTC+1 during the night of October 27, 2024.
+        assert_eq!(user.get_quiet_hours_end(utc("2024-10-26T21:00:00Z")), Some(utc("2024-10-27T06:00:00Z")));
+        // And from UTC+1 to UTC+2 during the night of March 31, 2024.
+        assert_eq!(user.get_quiet_hours_end(utc("2024-03-30T22:00:00Z")), Some(utc("2024-03-31T05:00:00Z")));
+    }
+
+    #[test]
+    fn get_quiet_hours_end_inside_a_dst_gap() {
+        // 02:30 does not exist in Paris on March 31, 2024, the clock jumps from 02:00 to 03:00.
+        let user = user(Some("Europe/Paris"), "22:00:00", "02:30:00");
+
+        assert_eq!(user.get_quiet_hours_end(utc("2024-03-30T22:00:00Z")), Some(utc("2024-03-31T01:30:00Z")));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,353 @@
+use std::{
+    collections::HashMap,
+    str::FromStr
+};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use cron::Schedule;
+use sqlx::types::Json;
+use core::{
+    error::Error,
+    config::Config
+};
+use types::{
+    extra_data::ExtraData,
+    identifier::Identifier
+};
+use crate::{
+    audience::Audience,
+    campaign_service::{CampaignService, NotificationCampaign},
+    notification_options::NotificationOptions,
+    redis_service::RedisService,
+    user_service::UserService,
+    rows::{
+        notification_row::NotificationRow,
+        schedule_from_row::{CountScheduleFromRow, ScheduleFromRow}
+    }
+};
+
+pub type ListOfScheduleFromRow = Vec<ScheduleFromRow>;
+
+pub struct ScheduleService {
+    redis_service: RedisService,
+    user_service: UserService,
+    campaign_service: CampaignService
+}
+
+impl ScheduleService {
+    pub fn new() -> Self {
+        ScheduleService {
+            redis_service: RedisService::new(),
+            user_service: UserService::new(),
+            campaign_service: CampaignService::new()
+        }
+    }
+}
+
+pub struct NotificationSchedule {
+    pub cron_expression: String,
+    pub timezone: String,
+    pub notification_type: String,
+    pub user_ids: Option<Vec<i32>>,
+    pub audience: Option<String>,
+    pub extra_data: Option<ExtraData>,
+    pub template_data: Option<HashMap<String, String>>
+}
+
+impl ScheduleService {
+    // None once the expression never fires again, like a date with a past year.
+    pub fn get_next_run_date(cron_expression: &str, timezone: &str, after: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, Error> {
+        // Standard 5 fields expressions are accepted, the seconds field is then fixed to 0.
+        let expression = if cron_expression.split_whitespace().count() == 5 {
+            format!("0 {}", cron_expression)
+        } else {
+            cron_expression.to_string()
+        };
+
+        let schedule = match Schedule::from_str(&expression) {
+            Ok(schedule) => schedule,
+            Err(_) => return Err(Error::ValidationError(format!("Invalid cron expression: {}", cron_expression)))
+        };
+        let timezone = match Tz::from_str(timezone) {
+            Ok(timezone) => timezone,
+            Err(_) => return Err(Error::ValidationError("timezone must be a valid IANA timezone".to_string()))
+        };
+
+        Ok(schedule.after(&after.with_timezone(&timezone)).next().map(|next_run_date| next_run_date.with_timezone(&Utc)))
+    }
+
+    pub async fn create(&mut self, app_id: i32, schedule: NotificationSchedule) -> Result<ScheduleFromRow, Error> {
+        let Some(next_run_date) = Self::get_next_run_date(&schedule.cron_expression, &schedule.timezone, Utc::now())? else {
+            return Err(Error::ValidationError(format!("Cron expression {} never fires", schedule.cron_expression)));
+        };
+        if let Some(audience) = &schedule.audience {
+            Audience::parse(audience)?;
+        }
+
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query(
+            r#"
+                INSERT INTO `notification_schedules`
+                    (app_id, cron_expression, timezone, notification_type, user_ids, audience, extra_data, template_data, next_run_date, creation_date)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, NOW())
+            "#
+        )
+            .bind::<i32>(app_id)
+            .bind::<String>(schedule.cron_expression)
+            .bind::<String>(schedule.timezone)
+            .bind::<String>(schedule.notification_type)
+            .bind::<Option<Json<Vec<i32>>>>(schedule.user_ids.map(Json))
+            .bind::<Option<String>>(schedule.audience)
+            .bind::<Option<Json<ExtraData>>>(schedule.extra_data.map(Json))
+            .bind::<Option<Json<HashMap<String, String>>>>(schedule.template_data.map(Json))
+            .bind::<DateTime<Utc>>(next_run_date)
+            .execute(&conn)
+            .await?;
+
+        self.find_schedule_by_id(app_id, Identifier(result.last_insert_id() as i32)).await
+    }
+
+    pub async fn find_schedule_by_id(&mut self, app_id: i32, schedule_id: Identifier) -> Result<ScheduleFromRow, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_as::<_, ScheduleFromRow>(
+            "SELECT * FROM `notification_schedules` WHERE id = ? AND app_id = ?"
+        )
+            .bind::<i32>(schedule_id.into())
+            .bind::<i32>(app_id)
+            .fetch_optional(&conn)
+            .await?;
+
+        match result {
+            Some(schedule) => Ok(schedule),
+            None => Err(Error::NotFoundError(format!("Schedule with id {} does not exists", schedule_id.0)))
+        }
+    }
+
+    pub async fn fetch_schedule_paginated(&mut self, app_id: i32, offset: i32, limit: i32) -> Result<ListOfScheduleFromRow, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_as::<_, ScheduleFromRow>(
+            r#"
+                SELECT * FROM `notification_schedules`
+                WHERE app_id = ?
+                ORDER BY id DESC
+                LIMIT ?
+                OFFSET ?
+            "#
+        )
+            .bind::<i32>(app_id)
+            .bind::<i32>(limit)
+            .bind::<i32>(offset)
+            .fetch_all(&conn)
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn count(&mut self, app_id: i32) -> Result<i32, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_as::<_, CountScheduleFromRow>(
+            "SELECT COUNT(*) as total_count FROM `notification_schedules` WHERE app_id = ?"
+        )
+            .bind::<i32>(app_id)
+            .fetch_one(&conn)
+            .await?;
+
+        Ok(result.total_count)
+    }
+
+    pub async fn pause(&mut self, app_id: i32, schedule_id: Identifier) -> Result<(), Error> {
+        self.find_schedule_by_id(app_id, schedule_id).await?;
+
+        let conn = Config::get_database_conn().await?;
+        sqlx::query(
+            "UPDATE `notification_schedules` SET is_paused = TRUE WHERE id = ?"
+        )
+            .bind::<i32>(schedule_id.into())
+            .execute(&conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn resume(&mut self, app_id: i32, schedule_id: Identifier) -> Result<(), Error> {
+        let schedule = self.find_schedule_by_id(app_id, schedule_id).await?;
+        // Occurrences missed while paused are skipped rather than sent all at once.
+        let Some(next_run_date) = Self::get_next_run_date(&schedule.cron_expression, &schedule.timezone, Utc::now())? else {
+            return Err(Error::ValidationError(format!("Cron expression {} never fires again", schedule.cron_expression)));
+        };
+
+        let conn = Config::get_database_conn().await?;
+        sqlx::query(
+            "UPDATE `notification_schedules` SET is_paused = FALSE, next_run_date = ? WHERE id = ?"
+        )
+            .bind::<DateTime<Utc>>(next_run_date)
+            .bind::<i32>(schedule_id.into())
+            .execute(&conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(&mut self, app_id: i32, schedule_id: Identifier) -> Result<(), Error> {
+        self.find_schedule_by_id(app_id, schedule_id).await?;
+
+        let conn = Config::get_database_conn().await?;
+        sqlx::query(
+            "DELETE FROM `notification_schedules` WHERE id = ?"
+        )
+            .bind::<i32>(schedule_id.into())
+            .execute(&conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn run_due_schedules(&mut self) -> Result<usize, Error> {
+        let now = Utc::now();
+        let conn = Config::get_database_conn().await?;
+        let schedules = sqlx::query_as::<_, ScheduleFromRow>(
+            r#"
+                SELECT * FROM `notification_schedules`
+                WHERE is_paused = FALSE AND next_run_date <= ?
+            "#
+        )
+            .bind::<DateTime<Utc>>(now)
+            .fetch_all(&conn)
+            .await?;
+
+        let mut enqueued = 0;
+        for schedule in &schedules {
+            // A failing schedule is logged, the other schedules still go out.
+            match self.run_schedule(schedule, now).await {
+                Ok(count) => enqueued += count,
+                Err(err) => println!("Could not run schedule {}: {}", schedule.id, String::from(err))
+            }
+        }
+
+        Ok(enqueued)
+    }
+}
+
+impl ScheduleService {
+    async fn run_schedule(&mut self, schedule: &ScheduleFromRow, now: DateTime<Utc>) -> Result<usize, Error> {
+        // Move the schedule forward first, so a failing run is never replayed in a loop.
+        // Only the consumer moving it from the occurrence it read enqueues that occurrence.
+        // A schedule without any next occurrence is paused once its last one is enqueued.
+        let next_run_date = Self::get_next_run_date(&schedule.cron_expression, &schedule.timezone, now)?;
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query(
+            r#"
+                UPDATE `notification_schedules` SET last_run_date = ?, next_run_date = COALESCE(?, next_run_date), is_paused = ?
+                WHERE id = ? AND next_run_date = ? AND is_paused = FALSE
+            "#
+        )
+            .bind::<DateTime<Utc>>(now)
+            .bind::<Option<DateTime<Utc>>>(next_run_date)
+            .bind::<bool>(next_run_date.is_none())
+            .bind::<i32>(schedule.id)
+            .bind::<DateTime<Utc>>(schedule.next_run_date)
+            .execute(&conn)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(0);
+        }
+
+        self.enqueue_schedule_notifications(schedule).await
+    }
+
+    async fn enqueue_schedule_notifications(&mut self, schedule: &ScheduleFromRow) -> Result<usize, Error> {
+        if let Some(user_ids) = &schedule.user_ids {
+            let user_ids = self.user_service.filter_existing_user_ids(schedule.app_id, &user_ids.0).await?;
+            for user_id in &user_ids {
+                self.enqueue_notification(schedule, *user_id).await?;
+            }
+
+            return Ok(user_ids.len());
+        }
+
+        // Like the audience sends, a campaign fans the occurrence out by chunks and counts its notifications.
+        let campaign = NotificationCampaign {
+            name: format!("Schedule {} occurrence", schedule.id),
+            notification_type: schedule.notification_type.clone(),
+            audience: schedule.audience.clone(),
+            extra_data: schedule.extra_data.as_ref().map(|extra_data| extra_data.0.clone()),
+            template_data: schedule.template_data.as_ref().map(|template_data| template_data.0.clone()),
+            options: NotificationOptions::default(),
+            rate: None,
+            scheduled_date: None
+        };
+        self.campaign_service.launch(schedule.app_id, campaign).await?;
+
+        Ok(0)
+    }
+
+    async fn enqueue_notification(&mut self, schedule: &ScheduleFromRow, user_id: i32) -> Result<(), Error> {
+        let notification = NotificationRow {
+            app_id: schedule.app_id,
+            user_id,
+            notification_type: schedule.notification_type.clone(),
+            number_of_tries: 0,
+            extra_data: schedule.extra_data.as_ref().map(|extra_data| extra_data.0.clone()),
+            template_data: schedule.template_data.as_ref().map(|template_data| serde_json::to_string(&template_data.0).unwrap()),
+            options: NotificationOptions::default(),
+            creation_date: Some(Utc::now()),
+            campaign_id: None,
+            throttle: None,
+            digest: None,
+        };
+
+        self.redis_service.create_notification(notification).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn get_next_run_date_accepts_five_fields_expressions() {
+        let after = utc("2026-10-19T09:30:00Z");
+
+        assert_eq!(ScheduleService::get_next_run_date("0 10 * * *", "UTC", after).unwrap(), Some(utc("2026-10-19T10:00:00Z")));
+        assert_eq!(
+            ScheduleService::get_next_run_date("0 10 * * *", "UTC", after).unwrap(),
+            ScheduleService::get_next_run_date("0 0 10 * * *", "UTC", after).unwrap()
+        );
+    }
+
+    #[test]
+    fn get_next_run_date_follows_the_timezone() {
+        assert_eq!(
+            ScheduleService::get_next_run_date("0 10 * * *", "Europe/Paris", utc("2026-06-01T00:00:00Z")).unwrap(),
+            Some(utc("2026-06-01T08:00:00Z"))
+        );
+        assert_eq!(
+            ScheduleService::get_next_run_date("0 10 * * *", "Europe/Paris", utc("2026-12-01T00:00:00Z")).unwrap(),
+            Some(utc("2026-12-01T09:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn get_next_run_date_is_none_once_the_expression_never_fires_again() {
+        let expression = "0 0 10 19 10 * 2026";
+
+        assert_eq!(
+            ScheduleService::get_next_run_date(expression, "UTC", utc("2026-10-19T09:00:00Z")).unwrap(),
+            Some(utc("2026-10-19T10:00:00Z"))
+        );
+        assert_eq!(ScheduleService::get_next_run_date(expression, "UTC", utc("2026-10-19T10:00:00Z")).unwrap(), None);
+    }
+
+    #[test]
+    fn get_next_run_date_rejects_invalid_settings() {
+        let after = utc("2026-10-19T09:00:00Z");
+
+        assert!(matches!(ScheduleService::get_next_run_date("every day", "UTC", after), Err(Error::ValidationError(_))));
+        assert!(matches!(ScheduleService::get_next_run_date("0 10 * * *", "Mars/Olympus", after), Err(Error::ValidationError(_))));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,176 @@
+use std::fmt::{Display, Write};
+use chrono::{DateTime, Utc};
+use minijinja::{
+    Environment,
+    UndefinedBehavior,
+    Value,
+    Error as TemplateError,
+    ErrorKind as TemplateErrorKind
+};
+use core::error::Error;
+
+pub struct TemplateRenderer {}
+
+impl TemplateRenderer {
+    const DEFAULT_DATE_FORMAT: &'static str = "%Y-%m-%d";
+
+    pub fn render(template: &str, context: &Value) -> Result<String, Error> {
+        match Self::get_environment().render_str(template, context) {
+            Ok(rendered) => Ok(rendered),
+            Err(err) => Err(Error::ValidationError(format!("Could not render template \"{}\": {}", template, err)))
+        }
+    }
+
+    pub fn validate(template: &str) -> Result<(), Error> {
+        match Self::get_environment().template_from_str(template) {
+            Ok(_) => Ok(()),
+            Err(err) => Err(Error::ValidationError(format!("Invalid template \"{}\": {}", template, err)))
+        }
+    }
+}
+
+impl TemplateRenderer {
+    fn get_environment() -> Environment<'static> {
+        let mut environment = Environment::new();
+        environment.set_undefined_behavior(UndefinedBehavior::Strict);
+        environment.add_filter("pluralize", pluralize);
+        environment.add_filter("number", number);
+        environment.add_filter("date", date);
+
+        environment
+    }
+}
+
+fn to_number(value: &Value) -> Result<f64, TemplateError> {
+    if value.is_undefined() {
+        return Err(TemplateError::from(TemplateErrorKind::UndefinedError));
+    }
+
+    if let Some(text) = value.as_str() {
+        return text.trim().parse::<f64>().map_err(|_| TemplateError::new(
+            TemplateErrorKind::InvalidOperation,
+            format!("\"{}\" is not a number", text)
+        ));
+    }
+
+    f64::try_from(value.clone())
+}
+
+fn pluralize(count: Value, singular: String, plural: Option<String>) -> Result<String, TemplateError> {
+    if to_number(&count)? == 1.0 {
+        return Ok(singular);
+    }
+
+    Ok(plural.unwrap_or(format!("{}s", singular)))
+}
+
+fn number(
+    value: Value,
+    decimals: Option<usize>,
+    thousands_separator: Option<String>,
+    decimal_separator: Option<String>
+) -> Result<String, TemplateError> {
+    let formatted = format!("{:.*}", decimals.unwrap_or(0), to_number(&value)?);
+    let (integer_part, decimal_part) = match formatted.split_once('.') {
+        Some((integer_part, decimal_part)) => (integer_part, Some(decimal_part)),
+        None => (formatted.as_str(), None)
+    };
+    let (sign, digits) = match integer_part.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", integer_part)
+    };
+
+    let thousands_separator = thousands_separator.unwrap_or(",".to_string());
+    let mut grouped = String::new();
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index) % 3 == 0 {
+            grouped.push_str(&thousands_separator);
+        }
+        grouped.push(digit);
+    }
+
+    match decimal_part {
+        Some(decimal_part) => Ok(format!(
+            "{}{}{}{}",
+            sign,
+            grouped,
+            decimal_separator.unwrap_or(".".to_string()),
+            decimal_part
+        )),
+        None => Ok(format!("{}{}", sign, grouped))
+    }
+}
+
+fn date(value: Value, format: Option<String>) -> Result<String, TemplateError> {
+    let format = format.unwrap_or(TemplateRenderer::DEFAULT_DATE_FORMAT.to_string());
+
+    if let Some(text) = value.as_str() {
+        if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+            return format_date(date.format(&format));
+        }
+    }
+
+    let timestamp = to_number(&value)? as i64;
+    match DateTime::<Utc>::from_timestamp(timestamp, 0) {
+        Some(date) => format_date(date.format(&format)),
+        None => Err(TemplateError::new(
+            TemplateErrorKind::InvalidOperation,
+            format!("{} is not a valid date", value)
+        ))
+    }
+}
+
+fn format_date(date: impl Display) -> Result<String, TemplateError> {
+    let mut formatted = String::new();
+    match write!(formatted, "{}", date) {
+        Ok(_) => Ok(formatted),
+        Err(_) => Err(TemplateError::new(TemplateErrorKind::InvalidOperation, "invalid date format"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(template: &str, context: serde_json::Value) -> Result<String, Error> {
+        TemplateRenderer::render(template, &Value::from_serialize(&context))
+    }
+
+    #[test]
+    fn pluralize_picks_the_form_from_the_count() {
+        let template = "{{ count }} {{ count | pluralize('item') }}, {{ count | pluralize('child', 'children') }}";
+
+        assert_eq!(render(template, json!({"count": 1})).unwrap(), "1 item, child");
+        assert_eq!(render(template, json!({"count": 0})).unwrap(), "0 items, children");
+        assert_eq!(render(template, json!({"count": "2"})).unwrap(), "2 items, children");
+        assert!(render(template, json!({"count": "many"})).is_err());
+    }
+
+    #[test]
+    fn number_groups_thousands_and_rounds_decimals() {
+        assert_eq!(render("{{ n | number }}", json!({"n": 1234567})).unwrap(), "1,234,567");
+        assert_eq!(render("{{ n | number }}", json!({"n": 999})).unwrap(), "999");
+        assert_eq!(render("{{ n | number(2) }}", json!({"n": -1234.567})).unwrap(), "-1,234.57");
+        assert_eq!(render("{{ n | number(1, ' ', ',') }}", json!({"n": "1234567.25"})).unwrap(), "1 234 567,2");
+        assert!(render("{{ n | number }}", json!({"n": "abc"})).is_err());
+        assert!(render("{{ missing | number }}", json!({})).is_err());
+    }
+
+    #[test]
+    fn date_formats_timestamps_and_rfc3339_strings() {
+        assert_eq!(render("{{ d | date }}", json!({"d": 0})).unwrap(), "1970-01-01");
+        assert_eq!(render("{{ d | date('%d/%m %H:%M') }}", json!({"d": 1700000000})).unwrap(), "14/11 22:13");
+        // The offset of the string is kept.
+        assert_eq!(render("{{ d | date('%H:%M') }}", json!({"d": "2024-03-10T08:30:00+02:00"})).unwrap(), "08:30");
+        assert!(render("{{ d | date }}", json!({"d": "yesterday"})).is_err());
+        assert!(render("{{ d | date('%Q') }}", json!({"d": 0})).is_err());
+    }
+
+    #[test]
+    fn render_fails_on_undefined_variables() {
+        assert!(render("Hello {{ name }}", json!({})).is_err());
+        assert!(TemplateRenderer::validate("Hello {{ name").is_err());
+        assert!(TemplateRenderer::validate("Hello {{ name | number }}").is_ok());
+    }
+}
\ No newline at end of file
@@ -1,48 +1,69 @@
 use std::collections::HashMap;
+use chrono::{NaiveTime, Utc};
+use sqlx::{MySql, QueryBuilder};
 use core::{
     user_context::UserContext,
     error::Error,
     config::Config
 };
 use types::{
+    enums::NotificationChannel,
+    extra_data::ExtraData,
     string::FirebaseToken,
-    enums::NotificationType,
     identifier::Identifier,
-    user::ListOfUsersWithExtraData
+    user::{DeviceSettings, ListOfUsersWithExtraData, UserAttributes, UserPreference}
 };
 use crate::{
+    app_service::AppService,
+    audience::Audience,
+    device_signature::DeviceSignature,
+    notification_options::NotificationOptions,
+    notification_template_service::NotificationTemplateService,
+    notification_rate::{NotificationRate, Throttle},
     redis_service::RedisService,
     rows::{
         notification_row::NotificationRow,
-        user_from_row::{CountUserFromRow, UserFromRow}
+        user_from_row::{CountUserFromRow, UserAttributeFromRow, UserFromRow, UserPreferenceFromRow}
     }
 };
 
 pub type ListOfUserFromRow = Vec<UserFromRow>;
 
 pub struct UserService {
-    redis_service: RedisService
+    redis_service: RedisService,
+    app_service: AppService
 }
 
 impl UserService {
     pub fn new() -> Self {
         UserService {
-            redis_service: RedisService::new()
+            redis_service: RedisService::new(),
+            app_service: AppService::new()
         }
     }
 }
 
 impl UserService {
-    pub async fn update_user_token_service(&mut self, user_context: &UserContext, token: FirebaseToken) -> Result<(), Error> {
+    pub const MAX_TAGS: usize = 50;
+    pub const MAX_ATTRIBUTES: usize = 50;
+
+    pub async fn update_user_token_service(
+        &mut self,
+        user_context: &UserContext,
+        token: FirebaseToken,
+        device_settings: DeviceSettings
+    ) -> Result<(), Error> {
+        self.check_device_signature(user_context).await?;
+
         match self.user_exist(&user_context).await {
             Ok(user_exist) => {
                 if !user_exist {
-                    self.create_user(&user_context, &token).await?;
+                    self.create_user(&user_context, &token, &device_settings).await?;
 
                     return Ok(())
                 }
 
-                self.update_user_token(&user_context, &token).await?;
+                self.update_user_token(&user_context, &token, &device_settings).await?;
                 Ok(())
             },
             Err(error) => Err(Error::ProviderError(error.into()))
@@ -51,12 +72,14 @@ impl UserService {
 
     pub async fn send_user_notification(
         &mut self,
+        app_id: i32,
         user_id: Identifier,
-        notification_type: NotificationType,
-        extra_data: Option<HashMap<String, String>>,
-        template_data: Option<HashMap<String, String>>
+        notification_type: String,
+        extra_data: Option<ExtraData>,
+        template_data: Option<HashMap<String, String>>,
+        options: NotificationOptions
     ) -> Result<(), Error> {
-        match self.user_exist_by_id(user_id.clone()).await {
+        match self.user_exist_by_id(app_id, user_id.clone()).await {
             Ok(result) => {
                 if !result {
                     return Err(Error::NotFoundError("User not found".to_string()));
@@ -65,18 +88,148 @@ impl UserService {
             Err(result) => return Err(Error::ProviderError(result.into()))
         }
 
-        let notification = NotificationRow {
-            user_id: user_id.into(),
-            notification_type: notification_type.into(),
-            number_of_tries: 0,
-            extra_data: if extra_data.is_none() { None } else { Option::from(serde_json::to_string(&extra_data.unwrap()).unwrap()) },
-            template_data: if template_data.is_none() { None } else { Option::from(serde_json::to_string(&template_data.unwrap()).unwrap()) },
-        };
-        self.redis_service.create_notification(notification).await?;
+        self.enqueue_notification(app_id, user_id.into(), notification_type, extra_data, template_data, options, None).await
+    }
+
+    pub async fn fetch_audience_user_ids_after(
+        &mut self,
+        app_id: i32,
+        audience: &Audience,
+        last_user_id: i32,
+        limit: i32
+    ) -> Result<Vec<i32>, Error> {
+        let conn = Config::get_database_conn().await?;
+        let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new("SELECT id FROM `users` WHERE app_id = ");
+        query_builder.push_bind(app_id);
+        query_builder.push(" AND id > ");
+        query_builder.push_bind(last_user_id);
+        query_builder.push(" AND ");
+        audience.push_condition(&mut query_builder);
+        query_builder.push(" ORDER BY id ASC LIMIT ");
+        query_builder.push_bind(limit);
+
+        let result = query_builder.build_query_scalar::<i32>()
+            .fetch_all(&conn)
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn find_user_attributes(&mut self, app_id: i32, user_id: Identifier) -> Result<UserAttributes, Error> {
+        let user = self.find_app_user_by_id(app_id, user_id).await?;
+
+        let conn = Config::get_database_conn().await?;
+        let tags = sqlx::query_scalar::<_, String>(
+            "SELECT tag FROM `user_tags` WHERE user_id = ? ORDER BY tag ASC"
+        )
+            .bind::<i32>(user.id)
+            .fetch_all(&conn)
+            .await?;
+        let attributes = sqlx::query_as::<_, UserAttributeFromRow>(
+            "SELECT name, value FROM `user_attributes` WHERE user_id = ?"
+        )
+            .bind::<i32>(user.id)
+            .fetch_all(&conn)
+            .await?;
+
+        Ok(UserAttributes {
+            tags,
+            attributes: attributes.into_iter().map(|attribute| (attribute.name, attribute.value)).collect()
+        })
+    }
+
+    pub async fn update_user_attributes(&mut self, app_id: i32, user_id: Identifier, user_attributes: UserAttributes) -> Result<(), Error> {
+        Self::check_user_attributes(&user_attributes)?;
+        let user = self.find_app_user_by_id(app_id, user_id).await?;
+
+        self.replace_user_attributes(user.id, &user_attributes).await
+    }
+
+    pub async fn update_device_attributes(&mut self, user_context: &UserContext, user_attributes: UserAttributes) -> Result<(), Error> {
+        Self::check_user_attributes(&user_attributes)?;
+        let conn = Config::get_database_conn().await?;
+        let user_id = sqlx::query_scalar::<_, i32>(
+            "SELECT id FROM `users` WHERE app_id = ? AND device_id = ? AND device_type = ?"
+        )
+            .bind::<i32>(user_context.app_id)
+            .bind::<String>(user_context.clone().device_id.into())
+            .bind::<String>(user_context.clone().device_type.to_string())
+            .fetch_optional(&conn)
+            .await?;
+
+        match user_id {
+            Some(user_id) => self.replace_user_attributes(user_id, &user_attributes).await,
+            None => Err(Error::NotFoundError("User not found".to_string()))
+        }
+    }
+
+    // Every category of the app is listed on every channel, those without a stored preference are enabled.
+    pub async fn find_user_preferences(&mut self, app_id: i32, user_id: Identifier) -> Result<Vec<UserPreference>, Error> {
+        let user = self.find_app_user_by_id(app_id, user_id).await?;
+        let categories = NotificationTemplateService::new().fetch_categories(app_id).await?;
+
+        let conn = Config::get_database_conn().await?;
+        let stored_preferences = sqlx::query_as::<_, UserPreferenceFromRow>(
+            "SELECT category, channel, enabled FROM `user_preferences` WHERE user_id = ?"
+        )
+            .bind::<i32>(user.id)
+            .fetch_all(&conn)
+            .await?;
+
+        let mut preferences: Vec<UserPreference> = Vec::from([]);
+        for category in categories {
+            for channel in NotificationChannel::ALL {
+                let enabled = stored_preferences.iter()
+                    .find(|preference| preference.category == category && preference.channel == channel.to_string())
+                    .map(|preference| preference.enabled)
+                    .unwrap_or(true);
+                preferences.push(UserPreference { category: category.clone(), channel, enabled });
+            }
+        }
+
+        Ok(preferences)
+    }
+
+    // Only the given preferences are changed, the other ones are kept.
+    pub async fn update_user_preferences(&mut self, app_id: i32, user_id: Identifier, preferences: Vec<UserPreference>) -> Result<(), Error> {
+        let user = self.find_app_user_by_id(app_id, user_id).await?;
+        let categories = NotificationTemplateService::new().fetch_categories(app_id).await?;
+        if let Some(preference) = preferences.iter().find(|preference| !categories.contains(&preference.category)) {
+            return Err(Error::ValidationError(format!("Unknown category: {}", preference.category)))
+        }
+        if preferences.is_empty() {
+            return Ok(())
+        }
+
+        let conn = Config::get_database_conn().await?;
+        let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new("INSERT INTO `user_preferences` (user_id, category, channel, enabled, update_date) ");
+        query_builder.push_values(&preferences, |mut values, preference| {
+            values.push_bind(user.id)
+                .push_bind(preference.category.clone())
+                .push_bind(preference.channel.to_string())
+                .push_bind(preference.enabled)
+                .push_bind(Utc::now());
+        });
+        query_builder.push(" ON DUPLICATE KEY UPDATE enabled = VALUES(enabled), update_date = VALUES(update_date)");
+        query_builder.build().execute(&conn).await?;
 
         Ok(())
     }
 
+    pub async fn is_opted_out(&mut self, user_id: i32, category: &str, channel: NotificationChannel) -> Result<bool, Error> {
+        let conn = Config::get_database_conn().await?;
+        let enabled = sqlx::query_scalar::<_, bool>(
+            "SELECT enabled FROM `user_preferences` WHERE user_id = ? AND category = ? AND channel = ?"
+        )
+            .bind::<i32>(user_id)
+            .bind::<&str>(category)
+            .bind::<String>(channel.to_string())
+            .fetch_optional(&conn)
+            .await?;
+
+        Ok(enabled.is_some_and(|enabled| !enabled))
+    }
+
     pub async fn find_user_by_id(&mut self, user_id: Identifier) -> Result<UserFromRow, Error> {
         let conn = Config::get_database_conn().await?;
         let result = sqlx::query_as::<_, UserFromRow>(
@@ -100,16 +253,28 @@ impl UserService {
         }
     }
 
-    pub async fn fetch_user_paginated(&mut self, offset: i32, limit: i32) -> Result<ListOfUserFromRow, Error> {
+    // Unlike find_user_by_id, used by the consumer, a user of another app is reported as missing.
+    pub async fn find_app_user_by_id(&mut self, app_id: i32, user_id: Identifier) -> Result<UserFromRow, Error> {
+        let user = self.find_user_by_id(user_id).await?;
+        if user.app_id != app_id {
+            return Err(Error::NotFoundError(format!("User with id {} does not exists", user_id.0)))
+        }
+
+        Ok(user)
+    }
+
+    pub async fn fetch_user_paginated(&mut self, app_id: i32, offset: i32, limit: i32) -> Result<ListOfUserFromRow, Error> {
         let conn = Config::get_database_conn().await?;
         let result = sqlx::query_as::<_, UserFromRow>(
             r#"
                 SELECT * FROM `users`
+                WHERE app_id = ?
                 ORDER BY id DESC
                 LIMIT ?
                 OFFSET ?
             "#
         )
+            .bind::<i32>(app_id)
             .bind::<i32>(limit)
             .bind::<i32>(offset)
             .fetch_all(&conn)
@@ -123,13 +288,14 @@ impl UserService {
         }
     }
 
-    pub async fn count(&mut self) -> Result<i32, Error> {
+    pub async fn count(&mut self, app_id: i32) -> Result<i32, Error> {
         let conn = Config::get_database_conn().await?;
         let result = sqlx::query_as::<_, CountUserFromRow>(
             r#"
-                SELECT COUNT(*) as total_count FROM `users`
+                SELECT COUNT(*) as total_count FROM `users` WHERE app_id = ?
             "#
         )
+            .bind::<i32>(app_id)
             .fetch_one(&conn)
             .await;
 
@@ -141,17 +307,102 @@ impl UserService {
         }
     }
 
+    pub async fn fetch_user_ids_after(&mut self, app_id: i32, last_user_id: i32, limit: i32) -> Result<Vec<i32>, Error> {
+        let conn = Config::get_database_conn().await?;
+        let result = sqlx::query_scalar::<_, i32>(
+            r#"
+                SELECT id FROM `users`
+                WHERE app_id = ? AND id > ?
+                ORDER BY id ASC
+                LIMIT ?
+            "#
+        )
+            .bind::<i32>(app_id)
+            .bind::<i32>(last_user_id)
+            .bind::<i32>(limit)
+            .fetch_all(&conn)
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn filter_existing_user_ids(&mut self, app_id: i32, user_ids: &[i32]) -> Result<Vec<i32>, Error> {
+        if user_ids.is_empty() {
+            return Ok(Vec::from([]))
+        }
+
+        let conn = Config::get_database_conn().await?;
+        let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new("SELECT id FROM `users` WHERE app_id = ");
+        query_builder.push_bind(app_id);
+        query_builder.push(" AND id IN (");
+        let mut separated = query_builder.separated(", ");
+        for user_id in user_ids {
+            separated.push_bind(*user_id);
+        }
+        separated.push_unseparated(") ORDER BY id ASC");
+
+        let result = query_builder.build_query_scalar::<i32>()
+            .fetch_all(&conn)
+            .await?;
+
+        Ok(result)
+    }
+
+    // Every given user in a single query, all the missing ones are reported at once.
+    pub async fn fetch_app_users_by_ids(&mut self, app_id: i32, user_ids: &[i32]) -> Result<ListOfUserFromRow, Error> {
+        if user_ids.is_empty() {
+            return Ok(Vec::from([]))
+        }
+
+        let conn = Config::get_database_conn().await?;
+        let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new("SELECT * FROM `users` WHERE app_id = ");
+        query_builder.push_bind(app_id);
+        query_builder.push(" AND id IN (");
+        let mut separated = query_builder.separated(", ");
+        for user_id in user_ids {
+            separated.push_bind(*user_id);
+        }
+        separated.push_unseparated(") ORDER BY id ASC");
+
+        let users = query_builder.build_query_as::<UserFromRow>()
+            .fetch_all(&conn)
+            .await?;
+
+        let mut missing_user_ids: Vec<i32> = user_ids.iter()
+            .filter(|user_id| !users.iter().any(|user| user.id == **user_id))
+            .copied()
+            .collect();
+        if !missing_user_ids.is_empty() {
+            missing_user_ids.sort();
+            missing_user_ids.dedup();
+            let missing_user_ids: Vec<String> = missing_user_ids.iter().map(|user_id| user_id.to_string()).collect();
+            return Err(Error::NotFoundError(format!("Users not found: {}", missing_user_ids.join(", "))));
+        }
+
+        Ok(users)
+    }
+
     pub async fn send_users_notification(
         &mut self,
+        app_id: i32,
         users_with_extra_data: ListOfUsersWithExtraData,
-        notification_type: NotificationType
+        notification_type: String,
+        rate: Option<NotificationRate>
     ) -> Result<(), Error> {
+        // Every user is checked first, a missing one must not leave the others half enqueued.
+        let user_ids: Vec<i32> = users_with_extra_data.iter().map(|user| user.id).collect();
+        self.fetch_app_users_by_ids(app_id, &user_ids).await?;
+
+        let throttle = self.get_throttle(rate).await?;
         for user in users_with_extra_data {
-            self.send_user_notification(
-                user.id.into(),
+            self.enqueue_notification(
+                app_id,
+                user.id,
                 notification_type.clone(),
                 user.extra_data,
-                user.template_data
+                user.template_data,
+                NotificationOptions::default(),
+                throttle.clone()
             ).await?;
         }
 
@@ -160,12 +411,146 @@ impl UserService {
 }
 
 impl UserService {
-    async fn update_user_token(&mut self, user_context: &UserContext, token: &FirebaseToken) -> Result<(), Error> {
+    // Anyone knowing a device id could otherwise replace its token, the backend of the app vouches for the device.
+    async fn check_device_signature(&mut self, user_context: &UserContext) -> Result<(), Error> {
+        let app = self.app_service.find_app_by_id(Identifier(user_context.app_id)).await?;
+        let secret = match app.get_device_registration_secret() {
+            Some(secret) => secret,
+            None => return Err(Error::ForbiddenError("Device registration secret is not configured for this app".to_string()))
+        };
+
+        let device_signature = match &user_context.device_signature {
+            Some(device_signature) => DeviceSignature::parse(device_signature)?,
+            None => return Err(Error::UnauthorizedError("Missing X-DEVICE-SIGNATURE header".to_string()))
+        };
+
+        device_signature.verify(&secret, &user_context.device_id)
+    }
+
+    async fn enqueue_notification(
+        &mut self,
+        app_id: i32,
+        user_id: i32,
+        notification_type: String,
+        extra_data: Option<ExtraData>,
+        template_data: Option<HashMap<String, String>>,
+        options: NotificationOptions,
+        throttle: Option<Throttle>
+    ) -> Result<(), Error> {
+        let notification = NotificationRow {
+            app_id,
+            user_id,
+            notification_type,
+            number_of_tries: 0,
+            extra_data,
+            template_data: if template_data.is_none() { None } else { Option::from(serde_json::to_string(&template_data.unwrap()).unwrap()) },
+            options,
+            creation_date: Some(Utc::now()),
+            campaign_id: None,
+            throttle,
+            digest: None,
+        };
+        self.redis_service.create_notification(notification).await?;
+
+        Ok(())
+    }
+
+    // Every notification of a bulk send shares the same bucket.
+    async fn get_throttle(&mut self, rate: Option<NotificationRate>) -> Result<Option<Throttle>, Error> {
+        match rate {
+            Some(rate) => Ok(Some(Throttle {
+                key: self.redis_service.get_next_throttle_key("bulk").await?,
+                rate
+            })),
+            None => Ok(None)
+        }
+    }
+
+    // Tags and attributes are replaced as a whole.
+    async fn replace_user_attributes(&mut self, user_id: i32, user_attributes: &UserAttributes) -> Result<(), Error> {
+        let conn = Config::get_database_conn().await?;
+        let mut transaction = conn.begin().await?;
+        sqlx::query("DELETE FROM `user_tags` WHERE user_id = ?")
+            .bind::<i32>(user_id)
+            .execute(&mut *transaction)
+            .await?;
+        sqlx::query("DELETE FROM `user_attributes` WHERE user_id = ?")
+            .bind::<i32>(user_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        if !user_attributes.tags.is_empty() {
+            let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new("INSERT IGNORE INTO `user_tags` (user_id, tag) ");
+            query_builder.push_values(&user_attributes.tags, |mut values, tag| {
+                values.push_bind(user_id).push_bind(tag.clone());
+            });
+            query_builder.build().execute(&mut *transaction).await?;
+        }
+
+        if !user_attributes.attributes.is_empty() {
+            let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new("INSERT INTO `user_attributes` (user_id, name, value) ");
+            query_builder.push_values(&user_attributes.attributes, |mut values, (name, value)| {
+                values.push_bind(user_id).push_bind(name.clone()).push_bind(value.clone());
+            });
+            query_builder.build().execute(&mut *transaction).await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    fn check_user_attributes(user_attributes: &UserAttributes) -> Result<(), Error> {
+        if user_attributes.tags.len() > Self::MAX_TAGS {
+            return Err(Error::ValidationError(format!("tags cannot contain more than {} values", Self::MAX_TAGS)))
+        }
+        if user_attributes.attributes.len() > Self::MAX_ATTRIBUTES {
+            return Err(Error::ValidationError(format!("attributes cannot contain more than {} values", Self::MAX_ATTRIBUTES)))
+        }
+
+        if let Some(tag) = user_attributes.tags.iter().find(|tag| !Audience::is_valid_name(tag)) {
+            return Err(Error::ValidationError(format!(
+                "Invalid tag \"{}\": only letters, digits, '_', '-' and '.' are allowed, up to {} characters", tag, Audience::MAX_NAME_LENGTH
+            )))
+        }
+
+        for (name, value) in &user_attributes.attributes {
+            if !Audience::is_valid_name(name) {
+                return Err(Error::ValidationError(format!(
+                    "Invalid attribute \"{}\": only letters, digits, '_', '-' and '.' are allowed, up to {} characters", name, Audience::MAX_NAME_LENGTH
+                )))
+            }
+            if Audience::DEVICE_FIELDS.contains(&name.as_str()) {
+                return Err(Error::ValidationError(format!("Attribute \"{}\" is a device field and cannot be overridden", name)))
+            }
+            if value.len() > Audience::MAX_VALUE_LENGTH {
+                return Err(Error::ValidationError(format!("Attribute \"{}\" cannot exceed {} characters", name, Audience::MAX_VALUE_LENGTH)))
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn update_user_token(
+        &mut self,
+        user_context: &UserContext,
+        token: &FirebaseToken,
+        device_settings: &DeviceSettings
+    ) -> Result<(), Error> {
         let conn = Config::get_database_conn().await?;
         sqlx::query(
-            "UPDATE `users` SET token = ? WHERE device_id = ? AND device_type = ?"
+            r#"
+                UPDATE `users`
+                SET token = ?, locale = ?, timezone = ?, quiet_hours_start = ?, quiet_hours_end = ?
+                WHERE app_id = ? AND device_id = ? AND device_type = ?
+            "#
         )
             .bind::<String>(token.clone().0)
+            .bind::<Option<String>>(device_settings.clone().locale)
+            .bind::<Option<String>>(device_settings.clone().timezone)
+            .bind::<Option<NaiveTime>>(device_settings.quiet_hours.map(|q| q.start))
+            .bind::<Option<NaiveTime>>(device_settings.quiet_hours.map(|q| q.end))
+            .bind::<i32>(user_context.app_id)
             .bind::<String>(user_context.clone().device_id.try_into().unwrap())
             .bind::<String>(user_context.clone().device_type.try_into().unwrap())
             .execute(&conn)
@@ -174,14 +559,28 @@ impl UserService {
         Ok({})
     }
 
-    async fn create_user(&mut self, user_context: &UserContext, token: &FirebaseToken) -> Result<(), Error> {
+    async fn create_user(
+        &mut self,
+        user_context: &UserContext,
+        token: &FirebaseToken,
+        device_settings: &DeviceSettings
+    ) -> Result<(), Error> {
         let conn = Config::get_database_conn().await?;
         sqlx::query(
-            "INSERT INTO `users` (device_id, device_type, token, creation_date) VALUES(?, ?, ?, NOW())"
+            r#"
+                INSERT INTO `users`
+                    (app_id, device_id, device_type, token, locale, timezone, quiet_hours_start, quiet_hours_end, creation_date)
+                    VALUES(?, ?, ?, ?, ?, ?, ?, ?, NOW())
+            "#
         )
+            .bind::<i32>(user_context.app_id)
             .bind::<String>(user_context.clone().device_id.try_into().unwrap())
             .bind::<String>(user_context.clone().device_type.try_into().unwrap())
             .bind::<String>(token.clone().0)
+            .bind::<Option<String>>(device_settings.clone().locale)
+            .bind::<Option<String>>(device_settings.clone().timezone)
+            .bind::<Option<NaiveTime>>(device_settings.quiet_hours.map(|q| q.start))
+            .bind::<Option<NaiveTime>>(device_settings.quiet_hours.map(|q| q.end))
             .execute(&conn)
             .await?;
 
@@ -191,8 +590,9 @@ impl UserService {
     async fn user_exist(&mut self, user_context: &UserContext) -> Result<bool, Error> {
         let conn = Config::get_database_conn().await?;
         let result = sqlx::query_as::<_, UserFromRow>(
-            "SELECT * FROM `users` WHERE device_id = ? AND device_type = ?"
+            "SELECT * FROM `users` WHERE app_id = ? AND device_id = ? AND device_type = ?"
         )
+            .bind::<i32>(user_context.app_id)
             .bind::<String>(user_context.clone().device_id.try_into().unwrap())
             .bind::<String>(user_context.clone().device_type.to_string())
             .fetch_optional(&conn)
@@ -212,12 +612,13 @@ impl UserService {
         }
     }
 
-    async fn user_exist_by_id(&mut self, user_id: Identifier) -> Result<bool, Error> {
+    async fn user_exist_by_id(&mut self, app_id: i32, user_id: Identifier) -> Result<bool, Error> {
         let conn = Config::get_database_conn().await?;
         let result = sqlx::query_as::<_, UserFromRow>(
-            "SELECT * FROM `users` WHERE id = ?"
+            "SELECT * FROM `users` WHERE id = ? AND app_id = ?"
         )
             .bind::<String>(user_id.into())
+            .bind::<i32>(app_id)
             .fetch_optional(&conn)
             .await;
 
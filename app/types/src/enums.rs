@@ -13,12 +13,6 @@ impl From<DeviceType> for String {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-pub enum NotificationType {
-    Test,
-    TestWithTemplate,
-}
-
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum NotificationStatus {
     InProgress,
@@ -33,6 +27,36 @@ impl From<NotificationStatus> for String {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CampaignStatus {
+    Draft,
+    Scheduled,
+    Running,
+    Paused,
+    Completed,
+    Canceled
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ApiKeyScope {
+    Register,
+    Send,
+    Read,
+    Admin
+}
+
+// Only push notifications are sent for now, preferences are kept by channel for the ones to come.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NotificationChannel {
+    Push
+}
+
+impl From<CampaignStatus> for String {
+    fn from(value: CampaignStatus) -> Self {
+        value.to_string()
+    }
+}
+
 impl fmt::Display for DeviceType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self)
@@ -51,51 +75,84 @@ impl FromStr for DeviceType {
     }
 }
 
-impl fmt::Display for NotificationType {
+impl fmt::Display for NotificationStatus {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self)
     }
 }
 
-impl FromStr for NotificationType {
+impl FromStr for NotificationStatus {
     type Err = ();
 
-    fn from_str(value: &str) -> Result<Self, Self::Err> {
-        match value {
-            "Test" => Ok(NotificationType::Test),
-            "TestWithTemplate" => Ok(NotificationType::TestWithTemplate),
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Sent" => Ok(NotificationStatus::Sent),
+            "Canceled" => Ok(NotificationStatus::Canceled),
+            "Failed" => Ok(NotificationStatus::Failed),
+            "InProgress" => Ok(NotificationStatus::InProgress),
             _ => Err(())
         }
     }
 }
 
-impl From<String> for NotificationType {
-    fn from(value: String) -> Self {
-        NotificationType::from_str(value.as_str()).unwrap()
+impl fmt::Display for CampaignStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
     }
 }
 
-impl From<NotificationType> for String {
-    fn from(value: NotificationType) -> Self {
-        value.to_string()
+impl FromStr for CampaignStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Draft" => Ok(CampaignStatus::Draft),
+            "Scheduled" => Ok(CampaignStatus::Scheduled),
+            "Running" => Ok(CampaignStatus::Running),
+            "Paused" => Ok(CampaignStatus::Paused),
+            "Completed" => Ok(CampaignStatus::Completed),
+            "Canceled" => Ok(CampaignStatus::Canceled),
+            _ => Err(())
+        }
     }
 }
 
-impl fmt::Display for NotificationStatus {
+impl fmt::Display for ApiKeyScope {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
     }
 }
 
-impl FromStr for NotificationStatus {
+impl FromStr for ApiKeyScope {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "Sent" => Ok(NotificationStatus::Sent),
-            "Canceled" => Ok(NotificationStatus::Canceled),
-            "Failed" => Ok(NotificationStatus::Failed),
-            "InProgress" => Ok(NotificationStatus::InProgress),
+            "register" => Ok(ApiKeyScope::Register),
+            "send" => Ok(ApiKeyScope::Send),
+            "read" => Ok(ApiKeyScope::Read),
+            "admin" => Ok(ApiKeyScope::Admin),
+            _ => Err(())
+        }
+    }
+}
+
+impl NotificationChannel {
+    pub const ALL: [NotificationChannel; 1] = [NotificationChannel::Push];
+}
+
+impl fmt::Display for NotificationChannel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl FromStr for NotificationChannel {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "push" => Ok(NotificationChannel::Push),
             _ => Err(())
         }
     }
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(transparent)]
+pub struct ExtraData<T = Map<String, Value>>(pub T);
+
+impl ExtraData {
+    pub const MAX_SIZE: usize = 4096;
+    pub const APS_KEY: &'static str = "aps";
+    const RESERVED_KEYS: [&'static str; 3] = ["from", "notification", "message_type"];
+    const RESERVED_PREFIXES: [&'static str; 2] = ["google.", "gcm."];
+
+    pub fn get_size(&self) -> usize {
+        serde_json::to_string(&self.0).map(|json| json.len()).unwrap_or_default()
+    }
+
+    pub fn get_violation(&self) -> Option<String> {
+        let reserved_key = self.0.keys().find(|key| {
+            Self::RESERVED_KEYS.contains(&key.as_str())
+                || Self::RESERVED_PREFIXES.iter().any(|prefix| key.starts_with(prefix))
+        });
+        if let Some(reserved_key) = reserved_key {
+            return Some(format!("extra_data key {} is reserved", reserved_key));
+        }
+
+        if self.get_size() > Self::MAX_SIZE {
+            return Some(format!("extra_data must not exceed {} bytes once serialized", Self::MAX_SIZE));
+        }
+
+        None
+    }
+
+    // FCM data only accepts strings, nested values are sent as their JSON representation.
+    // The aps dictionary only makes sense in the APNs payload.
+    pub fn get_flattened(&self) -> Map<String, Value> {
+        self.0.iter()
+            .filter(|(key, value)| !value.is_null() && key.as_str() != Self::APS_KEY)
+            .map(|(key, value)| match value {
+                Value::String(_) => (key.clone(), value.clone()),
+                _ => (key.clone(), Value::from(value.to_string()))
+            })
+            .collect()
+    }
+}
\ No newline at end of file
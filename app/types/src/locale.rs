@@ -0,0 +1,69 @@
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Locale<T = String>(pub T);
+
+impl Locale {
+    pub fn is_valid(&self) -> bool {
+        let mut subtags = self.0.split(['-', '_']);
+        let language = subtags.next().unwrap_or_default();
+        if !(2..=3).contains(&language.len()) || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+            return false;
+        }
+
+        subtags.all(|subtag| (2..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric()))
+    }
+
+    pub fn normalize(value: &str) -> String {
+        value.replace('_', "-").to_lowercase()
+    }
+
+    pub fn get_fallback_chain(&self) -> Vec<String> {
+        let normalized = Self::normalize(&self.0);
+        let mut chain = Vec::from([normalized.clone()]);
+
+        let mut current = normalized.as_str();
+        while let Some(position) = current.rfind('-') {
+            current = &current[..position];
+            chain.push(current.to_string());
+        }
+
+        chain
+    }
+}
+
+impl From<Locale> for String {
+    fn from(value: Locale) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(locale: &str) -> Vec<String> {
+        Locale(locale.to_string()).get_fallback_chain()
+    }
+
+    #[test]
+    fn get_fallback_chain_drops_subtags_from_the_most_specific() {
+        assert_eq!(chain("fr"), Vec::from(["fr"]));
+        assert_eq!(chain("pt-BR"), Vec::from(["pt-br", "pt"]));
+        assert_eq!(chain("zh-Hant-TW"), Vec::from(["zh-hant-tw", "zh-hant", "zh"]));
+    }
+
+    #[test]
+    fn get_fallback_chain_normalizes_underscores_and_case() {
+        assert_eq!(chain("en_US"), Vec::from(["en-us", "en"]));
+        assert_eq!(chain("EN-gb"), chain("en_GB"));
+    }
+
+    #[test]
+    fn is_valid_checks_the_language_and_subtags() {
+        assert!(Locale("fr".to_string()).is_valid());
+        assert!(Locale("zh_Hant_TW".to_string()).is_valid());
+        assert!(!Locale("".to_string()).is_valid());
+        assert!(!Locale("french".to_string()).is_valid());
+        assert!(!Locale("fr-".to_string()).is_valid());
+        assert!(!Locale("fr-F".to_string()).is_valid());
+    }
+}
\ No newline at end of file
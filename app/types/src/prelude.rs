@@ -3,4 +3,6 @@ pub mod device_id;
 pub mod dates;
 pub mod string;
 pub mod enums;
-pub mod user;
\ No newline at end of file
+pub mod user;
+pub mod locale;
+pub mod extra_data;
\ No newline at end of file
@@ -1,11 +1,42 @@
 use std::collections::HashMap;
+use chrono::NaiveTime;
 use serde::{Deserialize, Serialize};
+use crate::{
+    enums::NotificationChannel,
+    extra_data::ExtraData
+};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct UsersWithExtraDataDto {
     pub id: i32,
-    pub extra_data: Option<HashMap<String, String>>,
+    pub extra_data: Option<ExtraData>,
     pub template_data: Option<HashMap<String, String>>
 }
 
-pub type ListOfUsersWithExtraData = Vec<UsersWithExtraDataDto>;
\ No newline at end of file
+pub type ListOfUsersWithExtraData = Vec<UsersWithExtraDataDto>;
+
+#[derive(Clone, Debug, PartialEq, Eq, Copy)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DeviceSettings {
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub quiet_hours: Option<QuietHours>
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct UserAttributes {
+    pub tags: Vec<String>,
+    pub attributes: HashMap<String, String>
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UserPreference {
+    pub category: String,
+    pub channel: NotificationChannel,
+    pub enabled: bool
+}
\ No newline at end of file
@@ -3,42 +3,81 @@ use core::{
     config::Config
 };
 use dotenv::dotenv;
+use types::locale::Locale;
 use service::{
     notification_history_service::{NotificationHistoryService},
     notification_service::NotificationService,
     user_service::UserService,
+    schedule_service::ScheduleService,
+    campaign_service::CampaignService,
+    notification_catalog::NotificationCatalog,
+    notification_rate::NotificationRate,
     redis_service::{
         RedisService,
         ListOfRedisIdWithNotificationRow,
         ListOfRedisIdWithNotificationRowRef
     }
 };
-use std::thread;
+use std::{collections::HashMap, thread};
+use chrono::{Duration, Utc};
+use types::enums::{CampaignStatus, NotificationChannel};
 
 pub type ListOfRedisId<'a> = Vec<&'a String>;
-pub type Services = (RedisService, UserService, NotificationHistoryService, NotificationService);
+pub const PAUSED_CAMPAIGN_RETRY_DELAY: i64 = 30;
+pub type Services = (RedisService, UserService, NotificationHistoryService, NotificationService, ScheduleService, CampaignService);
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     dotenv().ok();
     Config::check_config().await?;
+    NotificationCatalog::init()?;
+    NotificationCatalog::watch()?;
 
     println!("Starting consumer");
     let redis_service = RedisService::new();
     let user_service = UserService::new();
     let notification_history_service = NotificationHistoryService::new();
-    let notification_service = NotificationService::new().await;
-    let services: &mut Services = &mut (redis_service, user_service, notification_history_service, notification_service);
+    let notification_service = NotificationService::new();
+    let schedule_service = ScheduleService::new();
+    let campaign_service = CampaignService::new();
+    let services: &mut Services = &mut (
+        redis_service,
+        user_service,
+        notification_history_service,
+        notification_service,
+        schedule_service,
+        campaign_service
+    );
 
     loop {
         consume(services).await?;
-        thread::sleep_ms(5000);
+        thread::sleep_ms(NotificationRate::CONSUMER_POLL_INTERVAL_MILLISECONDS as u32);
     }
 }
 
 async fn consume<'a>(
     services: &mut Services
 ) -> Result<(), Error> {
+    let scheduled = services.4.run_due_schedules().await?;
+    if scheduled > 0 {
+        println!("Enqueued {} scheduled notifications", scheduled);
+    }
+
+    let campaign_notifications = services.5.run_campaigns().await?;
+    if campaign_notifications > 0 {
+        println!("Enqueued {} campaign notifications", campaign_notifications);
+    }
+
+    let released = services.0.release_deferred_notifications().await?;
+    if released > 0 {
+        println!("Released {} deferred notifications", released);
+    }
+
+    let digests = services.0.release_due_digests().await?;
+    if digests > 0 {
+        println!("Released {} notification digests", digests);
+    }
+
     let notifications: ListOfRedisIdWithNotificationRow = services.0.fetch_notification_to_send().await?;
     consume_notifications(notifications, services).await?;
 
@@ -51,6 +90,17 @@ async fn consume_notifications<'a>(
 ) -> Result<(), Error> {
     let mut succeeded: ListOfRedisId = Vec::from([]);
     let mut failed: ListOfRedisIdWithNotificationRowRef = Vec::from([]);
+    let mut deferred: ListOfRedisId = Vec::from([]);
+    let mut expired: ListOfRedisId = Vec::from([]);
+    let mut rejected: ListOfRedisId = Vec::from([]);
+    let mut canceled: ListOfRedisId = Vec::from([]);
+    let mut paused: ListOfRedisId = Vec::from([]);
+    let mut throttled: ListOfRedisId = Vec::from([]);
+    let mut capped: ListOfRedisId = Vec::from([]);
+    let mut opted_out: ListOfRedisId = Vec::from([]);
+    let mut duplicated: ListOfRedisId = Vec::from([]);
+    let mut digested: ListOfRedisId = Vec::from([]);
+    let mut campaign_statuses: HashMap<i32, CampaignStatus> = HashMap::new();
 
     for (id, notification) in &notifications {
         let user = services.1.find_user_by_id(notification.user_id.into()).await?;
@@ -59,19 +109,189 @@ async fn consume_notifications<'a>(
             continue;
         }
 
-        services.3.send_notification(
+        if let Some(campaign_id) = notification.campaign_id {
+            let campaign_status = match campaign_statuses.get(&campaign_id) {
+                Some(campaign_status) => *campaign_status,
+                None => services.5.find_campaign_status(campaign_id).await?
+            };
+            campaign_statuses.insert(campaign_id, campaign_status);
+
+            if campaign_status == CampaignStatus::Canceled {
+                services.2.create(notification.clone().as_canceled_notification("campaign_canceled")).await?;
+                canceled.push(id);
+                continue;
+            }
+
+            if campaign_status == CampaignStatus::Paused {
+                services.0.defer_notification(id, notification, Utc::now() + Duration::seconds(PAUSED_CAMPAIGN_RETRY_DELAY)).await?;
+                paused.push(id);
+                continue;
+            }
+        }
+
+        let Some(information) = services.3.get_notification_information(notification).await? else {
+            println!("Missing key for notification {}: {}", id, notification.notification_type);
+            failed.push((id, notification));
+            continue;
+        };
+
+        if NotificationService::is_expired(notification, &information) {
+            services.2.create(notification.clone().as_canceled_notification("expired")).await?;
+            expired.push(id);
+            continue;
+        }
+
+        if let Some(category) = &information.category {
+            if services.1.is_opted_out(user.id, category, NotificationChannel::Push).await? {
+                services.2.create(notification.clone().as_canceled_notification("opted_out")).await?;
+                opted_out.push(id);
+                continue;
+            }
+        }
+
+        // Buffered notifications come back as a single digest row, which goes through the remaining checks.
+        if let Some(digest) = NotificationService::get_digest(notification, &information) {
+            services.0.buffer_digest_notification(notification, digest.get_window(), digest.get_max_count()).await?;
+            services.2.create(notification.clone().as_canceled_notification("digested")).await?;
+            digested.push(id);
+            continue;
+        }
+
+        if let Some(quiet_hours_end) = NotificationService::get_quiet_hours_end(&user, notification, &information) {
+            services.0.defer_quiet_hours_notification(id, notification, quiet_hours_end).await?;
+            deferred.push(id);
+            continue;
+        }
+
+        // Deferred notifications come back through this loop, the key of a notification throttled below is released.
+        let dedup_key = NotificationService::get_dedup_key(notification, &information);
+        if let Some((dedup_key, dedup_window)) = &dedup_key {
+            if !services.0.claim_dedup_key(dedup_key, *dedup_window).await? {
+                services.2.create(notification.clone().as_canceled_notification("duplicate")).await?;
+                duplicated.push(id);
+                continue;
+            }
+        }
+
+        let hourly_limit = Config::get_user_hourly_notification_limit();
+        if let Some(limit) = hourly_limit {
+            if services.0.increment_hourly_user_notifications(notification.user_id).await? > limit {
+                if let Some((dedup_key, _)) = &dedup_key {
+                    services.0.release_dedup_key(dedup_key).await?;
+                }
+                services.2.create(notification.clone().as_canceled_notification("user_hourly_limit")).await?;
+                capped.push(id);
+                continue;
+            }
+        }
+
+        // Only the notifications about to be sent take a token, the dropped ones must not slow the others down.
+        // The consumer never waits for a token, it stays free for the notifications of other apps and campaigns.
+        if let Some(throttle) = &notification.throttle {
+            let wait = services.0.take_token(throttle).await?;
+            if wait > 0 {
+                if hourly_limit.is_some() {
+                    services.0.decrement_hourly_user_notifications(notification.user_id).await?;
+                }
+                if let Some((dedup_key, _)) = &dedup_key {
+                    services.0.release_dedup_key(dedup_key).await?;
+                }
+                services.0.defer_notification(id, notification, Utc::now() + Duration::milliseconds(wait)).await?;
+                throttled.push(id);
+                continue;
+            }
+        }
+
+        let result = services.3.send_notification(
             &user.token.unwrap().into(),
             &user.device_type.into(),
-            &notification
-        ).await?;
+            &user.locale.map(Locale),
+            notification,
+            &information
+        ).await;
+
+        // Only sent notifications count towards the cap.
+        if result.is_err() && hourly_limit.is_some() {
+            services.0.decrement_hourly_user_notifications(notification.user_id).await?;
+        }
+
+        // Only sent notifications hold their dedup key.
+        if let (Err(_), Some((dedup_key, _))) = (&result, &dedup_key) {
+            services.0.release_dedup_key(dedup_key).await?;
+        }
 
-        services.2.create(notification.clone().as_sent_notification()).await?;
-        succeeded.push(id);
+        match result {
+            Ok(information) => {
+                services.2.create(notification.clone().as_sent_notification(&information)).await?;
+                succeeded.push(id);
+            },
+            Err(Error::PayloadTooLargeError(err)) => {
+                println!("Notification {} exceeds the provider payload limit: {}", id, err);
+                let mut history = notification.clone().as_failed_notification();
+                history.reason = Some("payload_too_large".to_string());
+                services.2.create(history).await?;
+                rejected.push(id);
+            },
+            Err(err) => {
+                println!("Failed to send notification {}: {}", id, String::from(err));
+                failed.push((id, notification));
+            }
+        }
     }
 
     services.0.delete_ids(succeeded.clone()).await?;
     println!("Consumed {} notifications", succeeded.clone().len());
 
+    if !expired.is_empty() {
+        println!("Dropped {} notifications whose ttl elapsed", expired.len());
+        services.0.delete_ids(expired).await?;
+    }
+
+    if !canceled.is_empty() {
+        println!("Canceled {} notifications of canceled campaigns", canceled.len());
+        services.0.delete_ids(canceled).await?;
+    }
+
+    if !paused.is_empty() {
+        println!("Deferred {} notifications of paused campaigns", paused.len());
+        services.0.delete_ids(paused).await?;
+    }
+
+    if !throttled.is_empty() {
+        println!("Deferred {} notifications exceeding their send rate", throttled.len());
+        services.0.delete_ids(throttled).await?;
+    }
+
+    if !opted_out.is_empty() {
+        println!("Canceled {} notifications of categories their user opted out of", opted_out.len());
+        services.0.delete_ids(opted_out).await?;
+    }
+
+    if !digested.is_empty() {
+        println!("Buffered {} notifications into digests", digested.len());
+        services.0.delete_ids(digested).await?;
+    }
+
+    if !duplicated.is_empty() {
+        println!("Dropped {} notifications already sent within their dedup window", duplicated.len());
+        services.0.delete_ids(duplicated).await?;
+    }
+
+    if !capped.is_empty() {
+        println!("Canceled {} notifications exceeding the hourly limit of their user", capped.len());
+        services.0.delete_ids(capped).await?;
+    }
+
+    if !rejected.is_empty() {
+        println!("Rejected {} notifications exceeding the provider payload limit", rejected.len());
+        services.0.delete_ids(rejected).await?;
+    }
+
+    if !deferred.is_empty() {
+        println!("Deferred {} notifications until quiet hours end", deferred.len());
+        services.0.delete_ids(deferred).await?;
+    }
+
     handle_failed_notifications(failed, services).await?;
 
     Ok(())